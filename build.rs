@@ -1,6 +1,16 @@
 use pkg_config::Config;
 use semver::{Version, VersionReq};
 
+/// HarfBuzz versions which added APIs we bind conditionally. For each one
+/// a `hb_MAJOR_MINOR` cfg is set when building against that or a newer
+/// version.
+const VERSIONS: &[(u64, u64)] = &[
+    (2, 4),
+    (3, 4),
+    (4, 0),
+    (5, 1),
+];
+
 fn main() {
     let lib = Config::new()
         .probe("harfbuzz")
@@ -12,4 +22,11 @@ fn main() {
     if !req.matches(&version) {
         panic!("Incompatible HarfBuzz version. Found {} but we require {}", version, req);
     }
+
+    for &(major, minor) in VERSIONS {
+        println!("cargo:rustc-check-cfg=cfg(hb_{}_{})", major, minor);
+        if (version.major, version.minor) >= (major, minor) {
+            println!("cargo:rustc-cfg=hb_{}_{}", major, minor);
+        }
+    }
 }
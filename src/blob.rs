@@ -1,16 +1,58 @@
 use cffi::Ptr;
 use std::{
+    ffi::CString,
+    fs::File,
+    io,
+    marker::PhantomData,
     mem,
+    ops::{Deref, DerefMut},
     os::raw,
+    path::Path,
     ptr,
     slice,
+    sync::Arc,
 };
 
 use crate::ffi;
 
+/// A blob of binary data, such as a font file.
+///
+/// A blob may borrow its data for `'a`. Blobs created from owned data are
+/// `Blob<'static>`.
 #[repr(transparent)]
-pub struct Blob(Ptr<ffi::hb_blob_t>);
-impl_ptr!(Blob, ffi::hb_blob_t);
+pub struct Blob<'a>(Ptr<ffi::hb_blob_t>, PhantomData<&'a [u8]>);
+
+impl<'a> Deref for Blob<'a> {
+    type Target = ffi::hb_blob_t;
+
+    fn deref(&self) -> &ffi::hb_blob_t {
+        unsafe { &*Ptr::as_ptr(&self.0) }
+    }
+}
+
+impl<'a> DerefMut for Blob<'a> {
+    fn deref_mut(&mut self) -> &mut ffi::hb_blob_t {
+        unsafe { &mut *Ptr::as_raw(&mut self.0) }
+    }
+}
+
+/// Cloning a blob creates a new reference to the same underlying object.
+///
+/// Both handles are made immutable, the same way HarfBuzz does when it shares
+/// a blob, for example with a face. Otherwise [`data_mut()`] on one handle
+/// could modify or free data borrowed through the other. This also applies
+/// to the original handle, so after cloning [`data_mut()`] panics on both;
+/// use [`copy_writable()`] to get a modifiable copy instead.
+impl<'a> Clone for Blob<'a> {
+    fn clone(&self) -> Blob<'a> {
+        unsafe {
+            let ptr = self.as_ptr() as *mut ffi::hb_blob_t;
+            ffi::hb_blob_make_immutable(ptr);
+            let ptr = ffi::hb_blob_reference(ptr);
+            Blob(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+}
 
 impl cffi::Alloc for ffi::hb_blob_t {
     fn free(this: *mut Self) {
@@ -18,34 +60,156 @@ impl cffi::Alloc for ffi::hb_blob_t {
     }
 }
 
-// unimplemented:
-// - hb_blob_create
-impl Blob {
+/// Destroy callback for blobs whose data is owned by a boxed `T`.
+pub(crate) unsafe extern "C" fn destroy_box<T>(data: *mut raw::c_void) {
+    drop(Box::from_raw(data as *mut T));
+}
+
+impl Blob<'static> {
     /// Returns the singleton empty blob.
-    pub fn empty() -> Blob {
+    pub fn empty() -> Blob<'static> {
         unsafe {
             let ptr = ffi::hb_blob_get_empty();
-            Blob(Ptr::from_raw(ptr))
+            Blob(Ptr::from_raw(ptr), PhantomData)
         }
     }
 
-    pub fn into_raw(self) -> *mut ffi::hb_blob_t {
-        Ptr::into_raw(self.0)
+    /// Create a blob taking ownership of `data`.
+    ///
+    /// The blob is created with [`WRITABLE`] memory mode, so HarfBuzz will
+    /// never copy the data.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `data` is longer than `c_uint::MAX` bytes.
+    pub fn from_vec(mut data: Vec<u8>) -> Blob<'static> {
+        let ptr = data.as_mut_ptr();
+        let len = data.len();
+        unsafe {
+            Blob::with_owner(data, ptr, len, ffi::hb_memory_mode_t::WRITABLE)
+        }
     }
 
-    pub fn as_ptr(&self) -> *const ffi::hb_blob_t {
-        Ptr::as_ptr(&self.0)
+    /// Create a blob taking ownership of `data`.
+    ///
+    /// The blob is created with [`WRITABLE`] memory mode, so HarfBuzz will
+    /// never copy the data.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `data` is longer than `c_uint::MAX` bytes.
+    pub fn from_boxed(mut data: Box<[u8]>) -> Blob<'static> {
+        let ptr = data.as_mut_ptr();
+        let len = data.len();
+        unsafe {
+            Blob::with_owner(data, ptr, len, ffi::hb_memory_mode_t::WRITABLE)
+        }
     }
 
-    pub fn as_raw(&mut self) -> *mut ffi::hb_blob_t {
-        Ptr::as_raw(&mut self.0)
+    /// Create a blob sharing `data`.
+    ///
+    /// Since `data` may be shared, the blob is created with [`READONLY`] memory
+    /// mode, and HarfBuzz will copy it if it ever needs to modify it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `data` is longer than `c_uint::MAX` bytes.
+    pub fn from_arc(data: Arc<[u8]>) -> Blob<'static> {
+        let ptr = data.as_ptr() as *mut u8;
+        let len = data.len();
+        unsafe {
+            Blob::with_owner(data, ptr, len, ffi::hb_memory_mode_t::READONLY)
+        }
+    }
+
+    /// Create a blob over static data.
+    ///
+    /// The blob is created with [`READONLY`] memory mode, and HarfBuzz will
+    /// copy the data if it ever needs to modify it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `data` is longer than `c_uint::MAX` bytes.
+    pub fn from_static(data: &'static [u8]) -> Blob<'static> {
+        Blob::from_slice(data)
+    }
+
+    /// Create a blob with contents of the file at `path`.
+    ///
+    /// HarfBuzz returns the empty blob both for empty files and for files it
+    /// couldn't read, in which case the file is inspected again to report
+    /// the actual error.
+    pub fn from_path(path: impl AsRef<Path>) -> io::Result<Blob<'static>> {
+        let path = path.as_ref();
+        let name = path_to_cstring(path)?;
+        let blob = unsafe {
+            let ptr = ffi::hb_blob_create_from_file(name.as_ptr());
+            Blob(Ptr::from_raw(ptr), PhantomData)
+        };
+
+        if blob.len() == 0 {
+            let metadata = File::open(path)?.metadata()?;
+            if metadata.is_dir() || metadata.len() != 0 {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("could not read {}", path.display()),
+                ));
+            }
+        }
+
+        Ok(blob)
+    }
+
+    /// Create a blob over `len` bytes at `data`, which are kept alive by
+    /// `owner`. `owner` is dropped once HarfBuzz no longer needs the data,
+    /// which may be immediately if creating the blob fails.
+    ///
+    /// ## Safety
+    ///
+    /// `data` must remain valid for as long as `owner` is alive, and if `mode`
+    /// is not [`READONLY`] it must also be safe to write to.
+    pub(crate) unsafe fn with_owner<T: 'static>(
+        owner: T,
+        data: *mut u8,
+        len: usize,
+        mode: ffi::hb_memory_mode_t,
+    ) -> Blob<'static> {
+        let len = blob_length(len);
+        let owner = Box::into_raw(Box::new(owner));
+        let ptr = ffi::hb_blob_create(
+            data as *const raw::c_char,
+            len,
+            mode,
+            owner as *mut raw::c_void,
+            Some(destroy_box::<T>),
+        );
+        Blob(Ptr::from_raw(ptr), PhantomData)
     }
 }
 
-// unimplemented:
-// - hb_blob_set_user_data
-// - hb_blob_get_user_data
-impl ffi::hb_blob_t {
+impl<'a> Blob<'a> {
+    /// Create a blob borrowing `data`.
+    ///
+    /// The blob is created with [`READONLY`] memory mode, and HarfBuzz will
+    /// copy the data if it ever needs to modify it. The borrow checker ensures
+    /// that `data` outlives the blob and anything created from it.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `data` is longer than `c_uint::MAX` bytes.
+    pub fn from_slice(data: &'a [u8]) -> Blob<'a> {
+        unsafe {
+            let ptr = ffi::hb_blob_create(
+                data.as_ptr() as *const raw::c_char,
+                blob_length(data.len()),
+                ffi::hb_memory_mode_t::READONLY,
+                ptr::null_mut(),
+                None,
+            );
+            Blob(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+
     /// Return a new blob that represents a range of bytes in this blob. The new
     /// blob is always created with [`READONLY`], meaning that it will never
     /// modify data in the parent blob. This blob's data is not expected
@@ -55,25 +219,81 @@ impl ffi::hb_blob_t {
     ///
     /// Return new blob, or the empty blob if something failed or if `length`
     /// is zero or `offset` is beyond the end of this blob's data.
-    pub fn create_sub_blob(&mut self, offset: usize, length: usize) -> Blob {
+    pub fn create_sub_blob(&mut self, offset: usize, length: usize) -> Blob<'a> {
         unsafe {
             let ptr = ffi::hb_blob_create_sub_blob(
-                self,
+                self.as_raw(),
                 offset as raw::c_uint,
                 length as raw::c_uint,
             );
-            Blob(Ptr::from_raw(ptr))
+            Blob(Ptr::from_raw(ptr), PhantomData)
         }
     }
 
+    /// Take ownership of a blob reference.
+    ///
+    /// ## Safety
+    ///
+    /// `ptr` must be a valid blob whose data outlives `'a`.
+    pub(crate) unsafe fn from_raw(ptr: *mut ffi::hb_blob_t) -> Blob<'a> {
+        Blob(Ptr::from_raw(ptr), PhantomData)
+    }
+
+    pub fn into_raw(self) -> *mut ffi::hb_blob_t {
+        Ptr::into_raw(self.0)
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_blob_t {
+        Ptr::as_ptr(&self.0)
+    }
+
+    pub fn as_raw(&mut self) -> *mut ffi::hb_blob_t {
+        Ptr::as_raw(&mut self.0)
+    }
+}
+
+#[cfg(unix)]
+fn path_to_cstring(path: &Path) -> io::Result<CString> {
+    use std::os::unix::ffi::OsStrExt;
+    CString::new(path.as_os_str().as_bytes()).map_err(|_| io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "path contains a nul byte",
+    ))
+}
+
+/// Outside of Unix HarfBuzz expects file names to be UTF-8.
+#[cfg(not(unix))]
+fn path_to_cstring(path: &Path) -> io::Result<CString> {
+    let path = path.to_str().ok_or_else(|| io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "path is not valid UTF-8",
+    ))?;
+    CString::new(path).map_err(|_| io::Error::new(
+        io::ErrorKind::InvalidInput,
+        "path contains a nul byte",
+    ))
+}
+
+/// HarfBuzz stores blob lengths as `unsigned int`.
+fn blob_length(len: usize) -> raw::c_uint {
+    if len > raw::c_uint::MAX as usize {
+        panic!("blob data too large: {} bytes", len);
+    }
+    len as raw::c_uint
+}
+
+// unimplemented:
+// - hb_blob_set_user_data
+// - hb_blob_get_user_data
+impl ffi::hb_blob_t {
     /// Makes a writable copy of @blob.
-    pub fn copy_writable(&mut self) -> Blob {
+    pub fn copy_writable(&mut self) -> Blob<'static> {
         unsafe {
             let ptr = ffi::hb_blob_copy_writable_or_fail(self);
             if ptr == ::std::ptr::null_mut() {
                 panic!("allocation failed");
             }
-            Blob(Ptr::from_raw(ptr))
+            Blob(Ptr::from_raw(ptr), PhantomData)
         }
     }
 
@@ -96,19 +316,63 @@ impl ffi::hb_blob_t {
         unsafe {
             // XXX: As of 1.8.8 hb_blob_get_data is constant, so this is safe.
             let ptr = ffi::hb_blob_get_data(mem::transmute(self), &mut len);
+            // The empty blob has no data at all.
+            if len == 0 {
+                return &[];
+            }
             slice::from_raw_parts(ptr as *const u8, len as usize)
         }
     }
 
     /// Try to make blob data writeable (possibly copying it).
+    ///
+    /// ## Panics
+    ///
+    /// Panics if this blob is immutable. Besides blobs made immutable with
+    /// [`make_immutable()`], this includes all non-empty blobs which were
+    /// cloned, including by [`Collection::new()`], or used to create a face.
+    /// Use [`copy_writable()`] to modify such blobs' data.
     pub fn data_mut(&mut self) -> &mut [u8] {
+        // The empty blob has no data, and is immutable.
+        if self.len() == 0 {
+            return &mut [];
+        }
+        if self.is_immutable() {
+            panic!("Cannot modify an immutable hb_blob_t");
+        }
         let mut len = 0;
         unsafe {
             let ptr = ffi::hb_blob_get_data_writable(self, &mut len);
-            if ptr != ptr::null_mut() {
+            if ptr == ptr::null_mut() {
                 panic!("allocation failed");
             }
             slice::from_raw_parts_mut(ptr as *mut u8, len as usize)
         }
     }
 }
+
+#[cfg(feature = "memmap")]
+use memmap2;
+
+#[cfg(feature = "memmap")]
+impl Blob<'static> {
+    /// Create a blob over a read-only memory map.
+    ///
+    /// The map is kept alive until HarfBuzz no longer needs it. The blob
+    /// is created with [`READONLY`] memory mode, so [`copy_writable()`]
+    /// and [`data_mut()`] will copy data instead of writing to the mapping.
+    ///
+    /// Note that the blob's contents are undefined if the underlying file
+    /// is modified while it is mapped.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `map` is longer than `c_uint::MAX` bytes.
+    pub fn from_mmap(map: memmap2::Mmap) -> Blob<'static> {
+        let ptr = map.as_ptr() as *mut u8;
+        let len = map.len();
+        unsafe {
+            Blob::with_owner(map, ptr, len, ffi::hb_memory_mode_t::READONLY)
+        }
+    }
+}
@@ -1,18 +1,79 @@
 use cffi::Ptr;
 use std::{
+    hash::{Hash, Hasher},
+    iter::FromIterator,
     mem,
     ops::Range,
     os::raw,
+    slice,
 };
 
 use crate::*;
 
+/// A buffer holding text to be shaped.
+///
+/// Shaping consumes the buffer and produces a [`GlyphBuffer`].
 #[repr(transparent)]
-pub struct Buffer(Ptr<ffi::hb_buffer_t>);
-impl_ptr!(Buffer, ffi::hb_buffer_t);
+pub struct UnicodeBuffer(Ptr<ffi::hb_buffer_t>);
+impl_ptr!(UnicodeBuffer, ffi::hb_buffer_t);
+
+/// A buffer holding positioned glyphs, the result of shaping
+/// a [`UnicodeBuffer`].
+///
+/// Once the glyphs are no longer needed the buffer can be reused for new text
+/// with [`clear()`].
+#[repr(transparent)]
+pub struct GlyphBuffer(Ptr<ffi::hb_buffer_t>);
+impl_ptr!(GlyphBuffer, ffi::hb_buffer_t);
 
 pub type GlyphInfo = ffi::hb_glyph_info_t;
+pub type GlyphFlags = ffi::hb_glyph_flags_t;
 pub type GlyphPosition = ffi::hb_glyph_position_t;
+pub type BufferFlags = ffi::hb_buffer_flags_t;
+pub type ClusterLevel = ffi::hb_buffer_cluster_level_t;
+pub type ContentType = ffi::hb_buffer_content_type_t;
+
+/// Properties of a run of text which affect how it's shaped.
+///
+/// Two sets of properties compare equal, and hash the same, when HarfBuzz
+/// would shape text with them the same way.
+#[derive(Clone, Copy, Debug)]
+pub struct SegmentProperties {
+    pub direction: Direction,
+    pub script: Script,
+    pub language: Language,
+}
+
+impl From<SegmentProperties> for ffi::hb_segment_properties_t {
+    fn from(props: SegmentProperties) -> ffi::hb_segment_properties_t {
+        ffi::hb_segment_properties_t::new(
+            props.direction, props.script, props.language)
+    }
+}
+
+impl From<ffi::hb_segment_properties_t> for SegmentProperties {
+    fn from(props: ffi::hb_segment_properties_t) -> SegmentProperties {
+        SegmentProperties {
+            direction: props.direction,
+            script: props.script,
+            language: props.language,
+        }
+    }
+}
+
+impl PartialEq for SegmentProperties {
+    fn eq(&self, other: &SegmentProperties) -> bool {
+        ffi::hb_segment_properties_t::from(*self) == (*other).into()
+    }
+}
+
+impl Eq for SegmentProperties {}
+
+impl Hash for SegmentProperties {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        ffi::hb_segment_properties_t::from(*self).hash(state)
+    }
+}
 
 impl cffi::Alloc for ffi::hb_buffer_t {
     fn free(ptr: *mut ffi::hb_buffer_t) {
@@ -22,74 +83,40 @@ impl cffi::Alloc for ffi::hb_buffer_t {
     }
 }
 
-impl Buffer {
+impl UnicodeBuffer {
     /// Create a new buffer with all properties set to defaults.
-    pub fn new() -> Buffer {
-        unsafe {
-            let raw = ffi::hb_buffer_create();
-            if 0 == ffi::hb_buffer_allocation_successful(raw) {
-                panic!("Cannot allocate hb_buffer_t: out of memory");
-            }
-            Buffer(Ptr::from_raw(raw))
-        }
+    pub fn new() -> UnicodeBuffer {
+        UnicodeBuffer(create_buffer(ffi::hb_buffer_content_type_t::UNICODE))
     }
 
     /// Create a new buffer with all properties set to defaults and specified
     /// capacity.
-    pub fn with_capacity(capacity: usize) -> Buffer {
+    pub fn with_capacity(capacity: usize) -> UnicodeBuffer {
         let mut buf = Self::new();
         buf.reserve(capacity);
         buf
     }
-}
-
-impl ffi::hb_buffer_t {
-    /// Reserve space for at least `capacity` items.
-    ///
-    /// Note that unlike [`Vec`] `capacity` is the _total_ capacity, not space
-    /// for additional elements.
-    pub fn reserve(&mut self, capacity: usize) {
-        unsafe {
-            ffi::hb_buffer_pre_allocate(self, capacity as raw::c_uint);
-            if 0 == ffi::hb_buffer_allocation_successful(self) {
-                panic!("Cannot reserve space for hb_buffer_t: out of memory");
-            }
-        }
-    }
 
     /// Resets the buffer to its initial status, as if it was just newly created
     /// with [`new()`].
     pub fn reset(&mut self) {
-        unsafe { ffi::hb_buffer_reset(self) }
+        unsafe {
+            ffi::hb_buffer_reset(self.as_raw());
+            ffi::hb_buffer_set_content_type(
+                self.as_raw(), ffi::hb_buffer_content_type_t::UNICODE);
+        }
     }
 
     /// Similar to [`hb_buffer_reset()`], but does not clear the Unicode
     /// functions and the replacement code point.
+    ///
+    /// This also clears segment properties, but keeps flags and cluster level.
     pub fn clear_contents(&mut self) {
-        unsafe { ffi::hb_buffer_clear_contents(self) }
-    }
-
-    /// Return the number of items in the buffer.
-    pub fn len(&self) -> usize {
-        // XXX: In 1.8.8 hb_buffer_get_length is constant.
-        unsafe { ffi::hb_buffer_get_length(mem::transmute(self)) as usize }
-    }
-
-    /// Set the text flow direction of the buffer. No shaping can happen without
-    /// setting buffer direction, and it controls the visual direction for the
-    /// output glyphs; for RTL direction the glyphs will be reversed. Many layout
-    /// features depend on the proper setting of the direction, for example,
-    /// reversing RTL text before shaping, then shaping with LTR direction is not
-    /// the same as keeping the text in logical order and shaping with RTL
-    /// direction.
-    pub fn set_direction(&mut self, dir: Direction) {
-        unsafe { ffi::hb_buffer_set_direction(self, dir) }
-    }
-
-    pub fn direction(&self) -> Direction {
-        // XXX: In 1.8.8 hb_buffer_get_direction is constant.
-        #[allow(mutable_transmutes)]
-        unsafe { ffi::hb_buffer_get_direction(mem::transmute(self)) }
+        unsafe {
+            ffi::hb_buffer_clear_contents(self.as_raw());
+            ffi::hb_buffer_set_content_type(
+                self.as_raw(), ffi::hb_buffer_content_type_t::UNICODE);
+        }
     }
 
     /// Sets unset buffer segment properties based on buffer Unicode contents.
@@ -111,22 +138,28 @@ impl ffi::hb_buffer_t {
     /// [`hb_language_get_default()`]. This may change in the future by taking
     /// buffer script into consideration when choosing a language.
     pub fn guess_segment_properties(&mut self) {
-        unsafe { ffi::hb_buffer_guess_segment_properties(self) }
+        unsafe { ffi::hb_buffer_guess_segment_properties(self.as_raw()) }
     }
 
     /// See [`add_codepoints()`].
     ///
     /// Replaces invalid UTF-8 characters with the `buffer` replacement code
     /// point, see [`set_replacement_codepoint()`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `slice` starts after it ends or extends past the end
+    /// of `codepoints`, or if `codepoints` has more than `c_int::MAX` items.
     pub fn add_utf8(&mut self, codepoints: &str, slice: Range<usize>) {
         let bytes = codepoints.as_bytes();
+        let (offset, length) = item_range(slice, bytes.len());
         unsafe {
             ffi::hb_buffer_add_utf8(
-                self,
+                self.as_raw(),
                 bytes.as_ptr() as *const raw::c_char,
                 bytes.len() as raw::c_int,
-                slice.start as raw::c_uint,
-                slice.end as raw::c_int,
+                offset,
+                length,
             )
         }
     }
@@ -135,14 +168,20 @@ impl ffi::hb_buffer_t {
     ///
     /// Replaces invalid UTF-16 characters with the `buffer` replacement code
     /// point, see [`set_replacement_codepoint()`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `slice` starts after it ends or extends past the end
+    /// of `codepoints`, or if `codepoints` has more than `c_int::MAX` items.
     pub fn add_utf16(&mut self, codepoints: &[u16], slice: Range<usize>) {
+        let (offset, length) = item_range(slice, codepoints.len());
         unsafe {
             ffi::hb_buffer_add_utf16(
-                self,
+                self.as_raw(),
                 codepoints.as_ptr(),
                 codepoints.len() as raw::c_int,
-                slice.start as raw::c_uint,
-                slice.end as raw::c_int,
+                offset,
+                length,
             )
         }
     }
@@ -151,14 +190,20 @@ impl ffi::hb_buffer_t {
     ///
     /// Replaces invalid UTF-32 characters with the `buffer` replacement code
     /// point, see [`set_replacement_codepoint()`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `slice` starts after it ends or extends past the end
+    /// of `codepoints`, or if `codepoints` has more than `c_int::MAX` items.
     pub fn add_utf32(&mut self, codepoints: &[char], slice: Range<usize>) {
+        let (offset, length) = item_range(slice, codepoints.len());
         unsafe {
             ffi::hb_buffer_add_utf32(
-                self,
+                self.as_raw(),
                 codepoints.as_ptr() as *const u32,
                 codepoints.len() as raw::c_int,
-                slice.start as raw::c_uint,
-                slice.end as raw::c_int,
+                offset,
+                length,
             )
         }
     }
@@ -167,14 +212,20 @@ impl ffi::hb_buffer_t {
     /// to first 256 Unicode code points that can fit in 8-bit strings.
     ///
     /// Note: Has nothing to do with non-Unicode Latin-1 encoding.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `slice` starts after it ends or extends past the end
+    /// of `codepoints`, or if `codepoints` has more than `c_int::MAX` items.
     pub fn add_latin1(&mut self, codepoints: &[u8], slice: Range<usize>) {
+        let (offset, length) = item_range(slice, codepoints.len());
         unsafe {
             ffi::hb_buffer_add_latin1(
-                self,
+                self.as_raw(),
                 codepoints.as_ptr(),
                 codepoints.len() as raw::c_int,
-                slice.start as raw::c_uint,
-                slice.end as raw::c_int,
+                offset,
+                length,
             )
         }
     }
@@ -191,38 +242,153 @@ impl ffi::hb_buffer_t {
     ///
     /// This function does not check the validity of `text`, it is up to
     /// the caller to ensure it contains a valid Unicode code points.
+    ///
+    /// Here `slice` stands for both `item_offset` and `item_length`: it is
+    /// the range of items of `codepoints` to append, and items outside it are
+    /// used only as context. For [`add_utf8()`]
+    /// and [`add_utf16()`] it is measured in code units, that is bytes and
+    /// `u16`s respectively, and cluster values of the appended characters are
+    /// their offsets in `codepoints` in the same units.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `slice` starts after it ends or extends past the end
+    /// of `codepoints`, or if `codepoints` has more than `c_int::MAX` items.
     pub fn add_codepoints(&mut self, codepoints: &[Codepoint], slice: Range<usize>) {
+        let (offset, length) = item_range(slice, codepoints.len());
         unsafe {
             ffi::hb_buffer_add_codepoints(
-                self,
+                self.as_raw(),
                 codepoints.as_ptr(),
                 codepoints.len() as raw::c_int,
-                slice.start as raw::c_uint,
-                slice.end as raw::c_int,
+                offset,
+                length,
             )
         }
     }
 
     /// Append (part of) contents of another buffer to this buffer.
-    pub fn append(&mut self, other: &ffi::hb_buffer_t, slice: Range<usize>) {
+    pub fn append(&mut self, other: &UnicodeBuffer, slice: Range<usize>) {
         unsafe {
             ffi::hb_buffer_append(
-                self,
+                self.as_raw(),
                 // In 1.8.8 `other` is never touched, so this is safe.
-                mem::transmute(other),
+                other.as_ptr() as *mut _,
                 slice.start as raw::c_uint,
                 slice.end as raw::c_uint,
             )
         }
     }
 
-    /// Reorders a glyph buffer to have canonical in-cluster glyph
-    /// order / position. The resulting clusters should behave identical
-    /// to pre-reordering clusters.
+    /// Shape contents of this buffer with `font`. See [`shape()`].
+    pub fn shape(self, font: &ffi::hb_font_t, features: &[Feature]) -> GlyphBuffer {
+        shape(font, self, features)
+    }
+
+    pub fn into_raw(self) -> *mut ffi::hb_buffer_t {
+        Ptr::into_raw(self.0)
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_buffer_t {
+        Ptr::as_ptr(&self.0)
+    }
+
+    pub fn as_raw(&mut self) -> *mut ffi::hb_buffer_t {
+        Ptr::as_raw(&mut self.0)
+    }
+}
+
+impl Default for UnicodeBuffer {
+    fn default() -> UnicodeBuffer {
+        UnicodeBuffer::new()
+    }
+}
+
+impl GlyphBuffer {
+    /// Create a new empty buffer for glyphs, with all properties set
+    /// to defaults.
     ///
-    /// Note: This has nothing to do with Unicode normalization.
-    pub fn normalize_glyphs(&mut self) {
-        unsafe { ffi::hb_buffer_normalize_glyphs(self) }
+    /// Glyphs can then be added with [`push()`] and positioned with
+    /// [`position()`], for example to lay out glyphs extracted from a document
+    /// without going through shaping.
+    pub fn new() -> GlyphBuffer {
+        GlyphBuffer(create_buffer(ffi::hb_buffer_content_type_t::GLYPHS))
+    }
+
+    /// Append `glyph` belonging to `cluster` to the buffer. Its position
+    /// is zeroed.
+    pub fn push(&mut self, glyph: Codepoint, cluster: u32) {
+        unsafe {
+            ffi::hb_buffer_add(self.as_raw(), glyph, cluster);
+            if 0 == ffi::hb_buffer_allocation_successful(self.as_raw()) {
+                panic!("Cannot reserve space for hb_buffer_t: out of memory");
+            }
+        }
+        // HarfBuzz doesn't initialize positions of added glyphs.
+        if let Some(position) = self.positions_mut().last_mut() {
+            *position = unsafe { mem::zeroed() };
+        }
+    }
+
+    /// Shorten the buffer to `len` glyphs. Has no effect if the buffer
+    /// is already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            unsafe {
+                ffi::hb_buffer_set_length(self.as_raw(), len as raw::c_uint);
+                // Emptying a buffer resets its content type.
+                ffi::hb_buffer_set_content_type(
+                    self.as_raw(), ffi::hb_buffer_content_type_t::GLYPHS);
+            }
+        }
+    }
+
+    /// Set positions of all glyphs to their default advances in `font`,
+    /// the same way HarfBuzz positions glyphs before applying any OpenType
+    /// positioning. Glyphs are laid out in buffer's direction, or left
+    /// to right if it wasn't set, in the order they are in the buffer.
+    pub fn position(&mut self, font: &ffi::hb_font_t) {
+        let direction = match self.direction() {
+            Direction::HB_DIRECTION_INVALID => Direction::HB_DIRECTION_LTR,
+            direction => direction,
+        };
+        let vertical = matches!(
+            direction,
+            Direction::HB_DIRECTION_TTB | Direction::HB_DIRECTION_BTT,
+        );
+        for (info, position) in self.iter_mut() {
+            let (x_advance, y_advance) =
+                font.glyph_advance_for_direction(info.codepoint, direction);
+            let (x_offset, y_offset) = if vertical {
+                font.subtract_glyph_origin_for_direction(info.codepoint, direction)
+            } else {
+                (0, 0)
+            };
+            position.x_advance = x_advance;
+            position.y_advance = y_advance;
+            position.x_offset = x_offset;
+            position.y_offset = y_offset;
+        }
+    }
+
+    /// Take ownership of a buffer which was just shaped.
+    ///
+    /// ## Safety
+    ///
+    /// `ptr` must be a valid buffer.
+    pub(crate) unsafe fn from_shaped(ptr: *mut ffi::hb_buffer_t) -> GlyphBuffer {
+        // HarfBuzz leaves empty buffers as they were.
+        ffi::hb_buffer_set_content_type(ptr, ffi::hb_buffer_content_type_t::GLYPHS);
+        GlyphBuffer(Ptr::from_raw(ptr))
+    }
+
+    /// Clear contents of this buffer, so that it can be reused for new text.
+    ///
+    /// This also clears segment properties, but keeps flags and cluster level.
+    pub fn clear(self) -> UnicodeBuffer {
+        let mut buffer = UnicodeBuffer(self.0);
+        buffer.clear_contents();
+        buffer
     }
 
     /// Get glyph informations.
@@ -230,8 +396,9 @@ impl ffi::hb_buffer_t {
         let mut len = 0;
         unsafe {
             // XXX: In 1.8.8 this function is constant.
-            let buf = ffi::hb_buffer_get_glyph_infos(mem::transmute(self), &mut len);
-            ::std::slice::from_raw_parts(buf, len as usize)
+            let buf = ffi::hb_buffer_get_glyph_infos(
+                self.as_ptr() as *mut _, &mut len);
+            glyph_slice(buf, len)
         }
     }
 
@@ -243,8 +410,9 @@ impl ffi::hb_buffer_t {
             // all it does is ensure that the returned array is correct (which
             // might mutate internal state). This is similar to how interior
             // mutability works in Rust.
-            let buf = ffi::hb_buffer_get_glyph_positions(mem::transmute(self), &mut len);
-            ::std::slice::from_raw_parts(buf, len as usize)
+            let buf = ffi::hb_buffer_get_glyph_positions(
+                self.as_ptr() as *mut _, &mut len);
+            glyph_slice(buf, len)
         }
     }
 
@@ -253,6 +421,279 @@ impl ffi::hb_buffer_t {
         self.infos().iter().zip(self.positions())
     }
 
+    /// Get mutable glyph informations.
+    pub fn infos_mut(&mut self) -> &mut [GlyphInfo] {
+        let mut len = 0;
+        unsafe {
+            let buf = ffi::hb_buffer_get_glyph_infos(self.as_raw(), &mut len);
+            glyph_slice_mut(buf, len)
+        }
+    }
+
+    /// Get mutable glyph positions, for example to adjust advances when
+    /// justifying text.
+    pub fn positions_mut(&mut self) -> &mut [GlyphPosition] {
+        let mut len = 0;
+        unsafe {
+            let buf = ffi::hb_buffer_get_glyph_positions(self.as_raw(), &mut len);
+            glyph_slice_mut(buf, len)
+        }
+    }
+
+    /// Get iterator over [`infos_mut()`] and [`positions_mut()`].
+    pub fn iter_mut(&mut self)
+    -> impl Iterator<Item = (&mut GlyphInfo, &mut GlyphPosition)> {
+        let mut len = 0;
+        unsafe {
+            let infos = ffi::hb_buffer_get_glyph_infos(self.as_raw(), &mut len);
+            let infos = glyph_slice_mut(infos, len);
+            let positions = ffi::hb_buffer_get_glyph_positions(self.as_raw(), &mut len);
+            let positions = glyph_slice_mut(positions, len);
+            infos.iter_mut().zip(positions)
+        }
+    }
+
+    /// Reorders a glyph buffer to have canonical in-cluster glyph
+    /// order / position. The resulting clusters should behave identical
+    /// to pre-reordering clusters.
+    ///
+    /// Note: This has nothing to do with Unicode normalization.
+    pub fn normalize_glyphs(&mut self) {
+        // hb_buffer_normalize_glyphs asserts that positions were set up,
+        // which isn't the case for buffers built manually or shaped from
+        // empty text. Asking for them sets them up.
+        self.positions_mut();
+        unsafe { ffi::hb_buffer_normalize_glyphs(self.as_raw()) }
+    }
+
+    /// Append (part of) contents of another buffer to this buffer.
+    pub fn append(&mut self, other: &GlyphBuffer, slice: Range<usize>) {
+        unsafe {
+            ffi::hb_buffer_append(
+                self.as_raw(),
+                // In 1.8.8 `other` is never touched, so this is safe.
+                other.as_ptr() as *mut _,
+                slice.start as raw::c_uint,
+                slice.end as raw::c_uint,
+            )
+        }
+    }
+
+    pub fn into_raw(self) -> *mut ffi::hb_buffer_t {
+        Ptr::into_raw(self.0)
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_buffer_t {
+        Ptr::as_ptr(&self.0)
+    }
+
+    pub fn as_raw(&mut self) -> *mut ffi::hb_buffer_t {
+        Ptr::as_raw(&mut self.0)
+    }
+}
+
+impl Default for GlyphBuffer {
+    fn default() -> GlyphBuffer {
+        GlyphBuffer::new()
+    }
+}
+
+/// Collects `(glyph, cluster)` pairs into a new buffer.
+impl FromIterator<(Codepoint, u32)> for GlyphBuffer {
+    fn from_iter<I: IntoIterator<Item = (Codepoint, u32)>>(iter: I) -> GlyphBuffer {
+        let mut buffer = GlyphBuffer::new();
+        buffer.extend(iter);
+        buffer
+    }
+}
+
+impl Extend<(Codepoint, u32)> for GlyphBuffer {
+    fn extend<I: IntoIterator<Item = (Codepoint, u32)>>(&mut self, iter: I) {
+        for (glyph, cluster) in iter {
+            self.push(glyph, cluster);
+        }
+    }
+}
+
+/// Convert `range` of items in a text `len` items long into item offset and
+/// length as expected by `hb_buffer_add_*`.
+fn item_range(range: Range<usize>, len: usize) -> (raw::c_uint, raw::c_int) {
+    if range.start > range.end {
+        panic!("item range starts at {} but ends at {}", range.start, range.end);
+    }
+    if range.end > len {
+        panic!("item range end {} out of range for text of length {}", range.end, len);
+    }
+    if len > raw::c_int::MAX as usize {
+        panic!("text too large: {} items", len);
+    }
+    (range.start as raw::c_uint, (range.end - range.start) as raw::c_int)
+}
+
+/// HarfBuzz returns a null array for buffers which never allocated any
+/// storage, which can't be turned into a slice.
+unsafe fn glyph_slice<'b, T>(ptr: *const T, len: raw::c_uint) -> &'b [T] {
+    if len == 0 { &[] } else { slice::from_raw_parts(ptr, len as usize) }
+}
+
+/// See [`glyph_slice()`].
+unsafe fn glyph_slice_mut<'b, T>(ptr: *mut T, len: raw::c_uint) -> &'b mut [T] {
+    if len == 0 { &mut [] } else { slice::from_raw_parts_mut(ptr, len as usize) }
+}
+
+fn create_buffer(content_type: ffi::hb_buffer_content_type_t) -> Ptr<ffi::hb_buffer_t> {
+    unsafe {
+        let raw = ffi::hb_buffer_create();
+        if 0 == ffi::hb_buffer_allocation_successful(raw) {
+            panic!("Cannot allocate hb_buffer_t: out of memory");
+        }
+        ffi::hb_buffer_set_content_type(raw, content_type);
+        Ptr::from_raw(raw)
+    }
+}
+
+impl ffi::hb_glyph_info_t {
+    /// Glyph index of this glyph, for a buffer which was shaped or built
+    /// from glyphs.
+    pub fn glyph_id(&self) -> Codepoint {
+        self.codepoint
+    }
+
+    /// Index of the first character, or byte in case of UTF-8, of the cluster
+    /// this glyph belongs to.
+    pub fn cluster(&self) -> u32 {
+        self.cluster
+    }
+
+    /// Return flags HarfBuzz set on this glyph while shaping.
+    ///
+    /// In particular a run of glyphs can be split without shaping it again
+    /// before any glyph which doesn't have [`GlyphFlags::UNSAFE_TO_BREAK`].
+    pub fn flags(&self) -> GlyphFlags {
+        unsafe { ffi::hb_glyph_info_get_glyph_flags(self) }
+    }
+}
+
+impl ffi::hb_buffer_t {
+    /// Reserve space for at least `capacity` items.
+    ///
+    /// Note that unlike [`Vec`] `capacity` is the _total_ capacity, not space
+    /// for additional elements.
+    pub fn reserve(&mut self, capacity: usize) {
+        unsafe {
+            ffi::hb_buffer_pre_allocate(self, capacity as raw::c_uint);
+            if 0 == ffi::hb_buffer_allocation_successful(self) {
+                panic!("Cannot reserve space for hb_buffer_t: out of memory");
+            }
+        }
+    }
+
+    /// Return the number of items in the buffer.
+    pub fn len(&self) -> usize {
+        unsafe { ffi::hb_buffer_get_length(self) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the type of the buffer's contents. Buffers always hold
+    /// [`UNICODE`] in [`UnicodeBuffer`], and [`GLYPHS`] in [`GlyphBuffer`].
+    pub fn content_type(&self) -> ContentType {
+        unsafe { ffi::hb_buffer_get_content_type(self) }
+    }
+
+    /// Set the text flow direction of the buffer. No shaping can happen without
+    /// setting buffer direction, and it controls the visual direction for the
+    /// output glyphs; for RTL direction the glyphs will be reversed. Many layout
+    /// features depend on the proper setting of the direction, for example,
+    /// reversing RTL text before shaping, then shaping with LTR direction is not
+    /// the same as keeping the text in logical order and shaping with RTL
+    /// direction.
+    pub fn set_direction(&mut self, dir: Direction) {
+        unsafe { ffi::hb_buffer_set_direction(self, dir) }
+    }
+
+    pub fn direction(&self) -> Direction {
+        unsafe { ffi::hb_buffer_get_direction(self) }
+    }
+
+    /// Set the script of the buffer. Script is crucial for choosing the proper
+    /// shaping behaviour for scripts that require it (e.g. Arabic) and which
+    /// OpenType features defined in the font to be applied.
+    pub fn set_script(&mut self, script: Script) {
+        unsafe { ffi::hb_buffer_set_script(self, script) }
+    }
+
+    /// Return the script of the buffer, or [`SCRIPT_INVALID`] if it wasn't
+    /// set.
+    pub fn script(&self) -> Script {
+        unsafe { ffi::hb_buffer_get_script(self) }
+    }
+
+    /// Set the language of the buffer. Languages are crucial for selecting
+    /// which OpenType feature to apply to the buffer which can result
+    /// in applying language-specific behaviour. Languages are orthogonal
+    /// to the scripts, and though they are related, they are different
+    /// concepts and should not be confused with each other.
+    pub fn set_language(&mut self, language: Language) {
+        unsafe { ffi::hb_buffer_set_language(self, language) }
+    }
+
+    /// Return the language of the buffer, or [`Language::INVALID`] if it
+    /// wasn't set.
+    pub fn language(&self) -> Language {
+        unsafe { ffi::hb_buffer_get_language(self) }
+    }
+
+    /// Set direction, script and language of the buffer at once.
+    pub fn set_segment_properties(&mut self, props: SegmentProperties) {
+        let props = ffi::hb_segment_properties_t::from(props);
+        unsafe { ffi::hb_buffer_set_segment_properties(self, &props) }
+    }
+
+    /// Return direction, script and language of the buffer.
+    pub fn segment_properties(&self) -> SegmentProperties {
+        let mut props = ffi::hb_segment_properties_t::new(
+            Direction::HB_DIRECTION_INVALID,
+            ffi::SCRIPT_INVALID,
+            Language::INVALID,
+        );
+        unsafe { ffi::hb_buffer_get_segment_properties(self, &mut props) }
+        props.into()
+    }
+
+    /// Set flags controlling how the buffer is shaped. See [`BufferFlags`].
+    pub fn set_flags(&mut self, flags: BufferFlags) {
+        unsafe { ffi::hb_buffer_set_flags(self, flags) }
+    }
+
+    pub fn flags(&self) -> BufferFlags {
+        unsafe { ffi::hb_buffer_get_flags(self) }
+    }
+
+    /// Set how characters are merged into clusters during shaping. See
+    /// [`ClusterLevel`].
+    pub fn set_cluster_level(&mut self, level: ClusterLevel) {
+        unsafe { ffi::hb_buffer_set_cluster_level(self, level) }
+    }
+
+    pub fn cluster_level(&self) -> ClusterLevel {
+        unsafe { ffi::hb_buffer_get_cluster_level(self) }
+    }
+
+    /// Set the code point which replaces invalid characters when adding text
+    /// to the buffer with [`add_utf8()`], [`add_utf16()`] and [`add_utf32()`].
+    ///
+    /// Default is [`HB_BUFFER_REPLACEMENT_CODEPOINT_DEFAULT`].
+    pub fn set_replacement_codepoint(&mut self, replacement: Codepoint) {
+        unsafe { ffi::hb_buffer_set_replacement_codepoint(self, replacement) }
+    }
+
+    pub fn replacement_codepoint(&self) -> Codepoint {
+        unsafe { ffi::hb_buffer_get_replacement_codepoint(self) }
+    }
+
     /// Reverse buffer contents.
     pub fn reverse(&mut self) {
         unsafe { ffi::hb_buffer_reverse(self) }
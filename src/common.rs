@@ -0,0 +1,523 @@
+use std::{
+    error::Error,
+    ffi::CStr,
+    fmt,
+    ops::{Bound, RangeBounds},
+    os::raw,
+    str::FromStr,
+};
+
+use crate::*;
+
+/// Create a [`Tag`] from a string literal, checking it at compile time.
+///
+/// Tags shorter than four characters are padded with spaces, so `tag!("cv1")`
+/// is the same as `Tag::new(*b"cv1 ")`.
+#[macro_export]
+macro_rules! tag {
+    ($tag:expr) => {{
+        const TAG: $crate::Tag = match $crate::Tag::parse($tag) {
+            Some(tag) => tag,
+            None => panic!("invalid OpenType tag"),
+        };
+        TAG
+    }};
+}
+
+impl ffi::hb_tag_t {
+    /// Parse a tag of one to four printable ASCII characters, padding it with
+    /// spaces the same way `hb_tag_from_string` does.
+    ///
+    /// This is a `const fn` version of [`str::parse()`]; see also [`tag!`].
+    pub const fn parse(s: &str) -> Option<Tag> {
+        let bytes = s.as_bytes();
+        if bytes.is_empty() || bytes.len() > 4 {
+            return None;
+        }
+        let mut tag = [b' '; 4];
+        let mut inx = 0;
+        while inx < bytes.len() {
+            if !matches!(bytes[inx], 0x20..=0x7e) {
+                return None;
+            }
+            tag[inx] = bytes[inx];
+            inx += 1;
+        }
+        Some(Tag::new(tag))
+    }
+}
+
+impl FromStr for ffi::hb_tag_t {
+    type Err = ParseTagError;
+
+    fn from_str(s: &str) -> Result<Tag, ParseTagError> {
+        Tag::parse(s).ok_or(ParseTagError(()))
+    }
+}
+
+/// Formats the tag as its four characters, including any trailing spaces.
+/// Bytes which aren't printable ASCII are escaped.
+impl fmt::Display for ffi::hb_tag_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for &byte in self.to_bytes().iter() {
+            if matches!(byte, 0x20..=0x7e) {
+                fmt::Write::write_char(fmt, byte as char)?;
+            } else {
+                write!(fmt, "\\x{:02x}", byte)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ffi::hb_tag_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "hb_tag_t({})", self)
+    }
+}
+
+/// Error returned when parsing a [`Tag`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseTagError(());
+
+impl fmt::Display for ParseTagError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("tag must be one to four printable ASCII characters")
+    }
+}
+
+impl Error for ParseTagError {}
+
+// Languages point to strings interned by HarfBuzz for the lifetime of the
+// process, which are never modified.
+unsafe impl Send for ffi::hb_language_t {}
+unsafe impl Sync for ffi::hb_language_t {}
+
+impl ffi::hb_language_t {
+    /// Unset language.
+    pub const INVALID: Language = ffi::HB_LANGUAGE_INVALID;
+
+    /// Return the default language of the process, as determined from its
+    /// locale.
+    ///
+    /// The locale is only read the first time this function is called,
+    /// and HarfBuzz caches the result.
+    pub fn default_for_process() -> Language {
+        unsafe { ffi::hb_language_get_default() }
+    }
+
+    pub fn is_invalid(self) -> bool {
+        self == Language::INVALID
+    }
+}
+
+/// Converts a BCP 47 language tag to a [`Language`]. The tag is
+/// canonicalized, so for example `"EN_us"` and `"en-us"` parse to the same
+/// language.
+impl FromStr for ffi::hb_language_t {
+    type Err = ParseLanguageError;
+
+    fn from_str(s: &str) -> Result<Language, ParseLanguageError> {
+        if s.is_empty() || s.len() > raw::c_int::MAX as usize {
+            return Err(ParseLanguageError(()));
+        }
+        let language = unsafe {
+            ffi::hb_language_from_string(
+                s.as_ptr() as *const raw::c_char,
+                s.len() as raw::c_int,
+            )
+        };
+        if language.is_invalid() {
+            Err(ParseLanguageError(()))
+        } else {
+            Ok(language)
+        }
+    }
+}
+
+/// Formats the language as a canonical BCP 47 language tag. Invalid language
+/// formats as an empty string.
+impl fmt::Display for ffi::hb_language_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_invalid() {
+            return Ok(());
+        }
+        let s = unsafe { CStr::from_ptr(ffi::hb_language_to_string(*self)) };
+        fmt.write_str(&s.to_string_lossy())
+    }
+}
+
+/// Error returned when parsing a [`Language`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseLanguageError(());
+
+impl fmt::Display for ParseLanguageError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid language tag")
+    }
+}
+
+impl Error for ParseLanguageError {}
+
+impl ffi::hb_script_t {
+    /// Convert an ISO 15924 script tag to a script.
+    ///
+    /// Returns [`SCRIPT_INVALID`] for the zero tag, and [`SCRIPT_UNKNOWN`]
+    /// for tags which aren't shaped like script tags.
+    pub fn from_iso15924_tag(tag: Tag) -> Script {
+        unsafe { ffi::hb_script_from_iso15924_tag(tag) }
+    }
+
+    /// Return the ISO 15924 tag of this script.
+    pub fn to_iso15924_tag(self) -> Tag {
+        unsafe { ffi::hb_script_to_iso15924_tag(self) }
+    }
+
+    /// Return the horizontal direction of text in this script, or
+    /// [`HB_DIRECTION_INVALID`] if it can be written in either direction
+    /// or its direction is not known.
+    pub fn horizontal_direction(self) -> Direction {
+        unsafe { ffi::hb_script_get_horizontal_direction(self) }
+    }
+
+    /// Iterate over all scripts known to these bindings, including
+    /// [`SCRIPT_COMMON`], [`SCRIPT_INHERITED`] and [`SCRIPT_UNKNOWN`].
+    pub fn all() -> impl Iterator<Item = Script> {
+        SCRIPTS.iter().cloned()
+    }
+}
+
+/// Parses a four letter ISO 15924 script tag, ignoring case. Tags of scripts
+/// HarfBuzz doesn't know about are accepted as well.
+impl FromStr for ffi::hb_script_t {
+    type Err = ParseScriptError;
+
+    fn from_str(s: &str) -> Result<Script, ParseScriptError> {
+        if s.len() != 4 || !s.bytes().all(|b| b.is_ascii_alphabetic()) {
+            return Err(ParseScriptError(()));
+        }
+        let script = unsafe {
+            ffi::hb_script_from_string(s.as_ptr() as *const raw::c_char, 4)
+        };
+        if script == ffi::SCRIPT_INVALID {
+            Err(ParseScriptError(()))
+        } else {
+            Ok(script)
+        }
+    }
+}
+
+/// Formats the script as its ISO 15924 tag. Invalid script formats as an empty
+/// string.
+impl fmt::Display for ffi::hb_script_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if *self == ffi::SCRIPT_INVALID {
+            return Ok(());
+        }
+        fmt::Display::fmt(&self.to_iso15924_tag(), fmt)
+    }
+}
+
+impl fmt::Debug for ffi::hb_script_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if *self == ffi::SCRIPT_INVALID {
+            fmt.write_str("SCRIPT_INVALID")
+        } else {
+            write!(fmt, "hb_script_t({})", self)
+        }
+    }
+}
+
+/// Error returned when parsing a [`Script`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseScriptError(());
+
+impl fmt::Display for ParseScriptError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("invalid script tag")
+    }
+}
+
+impl Error for ParseScriptError {}
+
+impl ffi::hb_feature_t {
+    /// Enable feature `tag` for the whole buffer.
+    pub fn on(tag: Tag) -> Feature {
+        Feature::value(tag, 1)
+    }
+
+    /// Disable feature `tag` for the whole buffer.
+    pub fn off(tag: Tag) -> Feature {
+        Feature::value(tag, 0)
+    }
+
+    /// Set feature `tag` to `value` for the whole buffer.
+    pub fn value(tag: Tag, value: u32) -> Feature {
+        Feature::range(tag, value, ..)
+    }
+
+    /// Set feature `tag` to `value` for clusters in `range`.
+    pub fn range(tag: Tag, value: u32, range: impl RangeBounds<u32>) -> Feature {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => ffi::HB_FEATURE_GLOBAL_START,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => ffi::HB_FEATURE_GLOBAL_END,
+        };
+        Feature { tag, value, start, end }
+    }
+}
+
+/// Parses a feature in the syntax accepted by `hb_feature_from_string`, which
+/// is similar to CSS `font-feature-settings`, for example `kern`, `-kern`,
+/// `kern=0`, `aalt=2`, `aalt[3:5]` or `"liga" on`.
+impl FromStr for ffi::hb_feature_t {
+    type Err = FeatureParseError;
+
+    fn from_str(s: &str) -> Result<Feature, FeatureParseError> {
+        let error = || FeatureParseError {
+            feature: s.to_string(),
+            offset: None,
+        };
+        if s.is_empty() || s.len() > raw::c_int::MAX as usize {
+            return Err(error());
+        }
+        let mut feature = Feature::on(Tag::new([0; 4]));
+        let r = unsafe {
+            ffi::hb_feature_from_string(
+                s.as_ptr() as *const raw::c_char,
+                s.len() as raw::c_int,
+                &mut feature,
+            )
+        };
+        if r != 0 { Ok(feature) } else { Err(error()) }
+    }
+}
+
+/// Formats the feature in the syntax accepted by [`str::parse()`].
+impl fmt::Display for ffi::hb_feature_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        // HarfBuzz never writes more than 128 bytes.
+        let mut buf = [0 as raw::c_char; 128];
+        let mut feature = *self;
+        let s = unsafe {
+            ffi::hb_feature_to_string(
+                &mut feature, buf.as_mut_ptr(), buf.len() as raw::c_uint);
+            CStr::from_ptr(buf.as_ptr())
+        };
+        fmt.write_str(&s.to_string_lossy())
+    }
+}
+
+/// Parse a comma separated list of features, such as `"liga,-kern,ss01"`.
+///
+/// White space around features is ignored, as is an empty list.
+pub fn parse_features(s: &str) -> Result<Vec<Feature>, FeatureParseError> {
+    parse_list(s, |item, offset| {
+        item.parse::<Feature>()
+            .map_err(|err| FeatureParseError { offset: Some(offset), ..err })
+    })
+}
+
+/// Parse a comma separated list, passing each trimmed item and its byte
+/// offset in `s` to `parse`.
+fn parse_list<T, E>(s: &str, mut parse: impl FnMut(&str, usize) -> Result<T, E>)
+-> Result<Vec<T>, E> {
+    if s.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut offset = 0;
+    s.split(',')
+        .map(|item| {
+            let item_offset = offset + item.len() - item.trim_start().len();
+            offset += item.len() + 1;
+            parse(item.trim(), item_offset)
+        })
+        .collect()
+}
+
+/// Error returned when parsing a [`Feature`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeatureParseError {
+    feature: String,
+    offset: Option<usize>,
+}
+
+impl FeatureParseError {
+    /// Return the feature which failed to parse.
+    pub fn feature(&self) -> &str {
+        &self.feature
+    }
+
+    /// Return byte offset of the feature which failed to parse in the string
+    /// passed to [`parse_features()`].
+    ///
+    /// This is `None` for errors from parsing a single feature with
+    /// [`str::parse()`], where the offset is always 0.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+}
+
+impl fmt::Display for FeatureParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "invalid feature {:?}", self.feature)?;
+        if let Some(offset) = self.offset {
+            write!(fmt, " at offset {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for FeatureParseError {}
+
+impl ffi::hb_variation_t {
+    /// Set variation axis `tag` to `value`.
+    pub fn new(tag: Tag, value: f32) -> Variation {
+        Variation { tag, value }
+    }
+}
+
+/// Parses a variation in the syntax accepted by `hb_variation_from_string`,
+/// which is similar to CSS `font-variation-settings`, for example `wght=650`
+/// or `"wdth" 75`.
+impl FromStr for ffi::hb_variation_t {
+    type Err = VariationParseError;
+
+    fn from_str(s: &str) -> Result<Variation, VariationParseError> {
+        let error = || VariationParseError {
+            variation: s.to_string(),
+            offset: None,
+        };
+        if s.is_empty() || s.len() > raw::c_int::MAX as usize {
+            return Err(error());
+        }
+        let mut variation = Variation::new(Tag::new([0; 4]), 0.0);
+        let r = unsafe {
+            ffi::hb_variation_from_string(
+                s.as_ptr() as *const raw::c_char,
+                s.len() as raw::c_int,
+                &mut variation,
+            )
+        };
+        if r != 0 { Ok(variation) } else { Err(error()) }
+    }
+}
+
+/// Formats the variation in the syntax accepted by [`str::parse()`].
+impl fmt::Display for ffi::hb_variation_t {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        // HarfBuzz never writes more than 128 bytes.
+        let mut buf = [0 as raw::c_char; 128];
+        let mut variation = *self;
+        let s = unsafe {
+            ffi::hb_variation_to_string(
+                &mut variation, buf.as_mut_ptr(), buf.len() as raw::c_uint);
+            CStr::from_ptr(buf.as_ptr())
+        };
+        fmt.write_str(&s.to_string_lossy())
+    }
+}
+
+/// Parse a comma separated list of variations, such as `"wght=650,wdth=75"`.
+///
+/// White space around variations is ignored, as is an empty list.
+pub fn parse_variations(s: &str) -> Result<Vec<Variation>, VariationParseError> {
+    parse_list(s, |item, offset| {
+        item.parse::<Variation>()
+            .map_err(|err| VariationParseError { offset: Some(offset), ..err })
+    })
+}
+
+/// Error returned when parsing a [`Variation`] fails.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VariationParseError {
+    variation: String,
+    offset: Option<usize>,
+}
+
+impl VariationParseError {
+    /// Return the variation which failed to parse.
+    pub fn variation(&self) -> &str {
+        &self.variation
+    }
+
+    /// Return byte offset of the variation which failed to parse in the string
+    /// passed to [`parse_variations()`].
+    ///
+    /// This is `None` for errors from parsing a single variation with
+    /// [`str::parse()`], where the offset is always 0.
+    pub fn offset(&self) -> Option<usize> {
+        self.offset
+    }
+}
+
+impl fmt::Display for VariationParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "invalid variation {:?}", self.variation)?;
+        if let Some(offset) = self.offset {
+            write!(fmt, " at offset {}", offset)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for VariationParseError {}
+
+const SCRIPTS: &[Script] = &[
+    ffi::SCRIPT_COMMON, ffi::SCRIPT_INHERITED, ffi::SCRIPT_UNKNOWN,
+    ffi::SCRIPT_ARABIC, ffi::SCRIPT_ARMENIAN, ffi::SCRIPT_BENGALI,
+    ffi::SCRIPT_CYRILLIC, ffi::SCRIPT_DEVANAGARI, ffi::SCRIPT_GEORGIAN,
+    ffi::SCRIPT_GREEK, ffi::SCRIPT_GUJARATI, ffi::SCRIPT_GURMUKHI,
+    ffi::SCRIPT_HANGUL, ffi::SCRIPT_HAN, ffi::SCRIPT_HEBREW,
+    ffi::SCRIPT_HIRAGANA, ffi::SCRIPT_KANNADA, ffi::SCRIPT_KATAKANA,
+    ffi::SCRIPT_LAO, ffi::SCRIPT_LATIN, ffi::SCRIPT_MALAYALAM,
+    ffi::SCRIPT_ORIYA, ffi::SCRIPT_TAMIL, ffi::SCRIPT_TELUGU, ffi::SCRIPT_THAI,
+    ffi::SCRIPT_TIBETAN, ffi::SCRIPT_BOPOMOFO, ffi::SCRIPT_BRAILLE,
+    ffi::SCRIPT_CANADIAN_SYLLABICS, ffi::SCRIPT_CHEROKEE, ffi::SCRIPT_ETHIOPIC,
+    ffi::SCRIPT_KHMER, ffi::SCRIPT_MONGOLIAN, ffi::SCRIPT_MYANMAR,
+    ffi::SCRIPT_OGHAM, ffi::SCRIPT_RUNIC, ffi::SCRIPT_SINHALA,
+    ffi::SCRIPT_SYRIAC, ffi::SCRIPT_THAANA, ffi::SCRIPT_YI, ffi::SCRIPT_DESERET,
+    ffi::SCRIPT_GOTHIC, ffi::SCRIPT_OLD_ITALIC, ffi::SCRIPT_BUHID,
+    ffi::SCRIPT_HANUNOO, ffi::SCRIPT_TAGALOG, ffi::SCRIPT_TAGBANWA,
+    ffi::SCRIPT_CYPRIOT, ffi::SCRIPT_LIMBU, ffi::SCRIPT_LINEAR_B,
+    ffi::SCRIPT_OSMANYA, ffi::SCRIPT_SHAVIAN, ffi::SCRIPT_TAI_LE,
+    ffi::SCRIPT_UGARITIC, ffi::SCRIPT_BUGINESE, ffi::SCRIPT_COPTIC,
+    ffi::SCRIPT_GLAGOLITIC, ffi::SCRIPT_KHAROSHTHI, ffi::SCRIPT_NEW_TAI_LUE,
+    ffi::SCRIPT_OLD_PERSIAN, ffi::SCRIPT_SYLOTI_NAGRI, ffi::SCRIPT_TIFINAGH,
+    ffi::SCRIPT_BALINESE, ffi::SCRIPT_CUNEIFORM, ffi::SCRIPT_NKO,
+    ffi::SCRIPT_PHAGS_PA, ffi::SCRIPT_PHOENICIAN, ffi::SCRIPT_CARIAN,
+    ffi::SCRIPT_CHAM, ffi::SCRIPT_KAYAH_LI, ffi::SCRIPT_LEPCHA,
+    ffi::SCRIPT_LYCIAN, ffi::SCRIPT_LYDIAN, ffi::SCRIPT_OL_CHIKI,
+    ffi::SCRIPT_REJANG, ffi::SCRIPT_SAURASHTRA, ffi::SCRIPT_SUNDANESE,
+    ffi::SCRIPT_VAI, ffi::SCRIPT_AVESTAN, ffi::SCRIPT_BAMUM,
+    ffi::SCRIPT_EGYPTIAN_HIEROGLYPHS, ffi::SCRIPT_IMPERIAL_ARAMAIC,
+    ffi::SCRIPT_INSCRIPTIONAL_PAHLAVI, ffi::SCRIPT_INSCRIPTIONAL_PARTHIAN,
+    ffi::SCRIPT_JAVANESE, ffi::SCRIPT_KAITHI, ffi::SCRIPT_LISU,
+    ffi::SCRIPT_MEETEI_MAYEK, ffi::SCRIPT_OLD_SOUTH_ARABIAN,
+    ffi::SCRIPT_OLD_TURKIC, ffi::SCRIPT_SAMARITAN, ffi::SCRIPT_TAI_THAM,
+    ffi::SCRIPT_TAI_VIET, ffi::SCRIPT_BATAK, ffi::SCRIPT_BRAHMI,
+    ffi::SCRIPT_MANDAIC, ffi::SCRIPT_CHAKMA, ffi::SCRIPT_MEROITIC_CURSIVE,
+    ffi::SCRIPT_MEROITIC_HIEROGLYPHS, ffi::SCRIPT_MIAO, ffi::SCRIPT_SHARADA,
+    ffi::SCRIPT_SORA_SOMPENG, ffi::SCRIPT_TAKRI, ffi::SCRIPT_BASSA_VAH,
+    ffi::SCRIPT_CAUCASIAN_ALBANIAN, ffi::SCRIPT_DUPLOYAN, ffi::SCRIPT_ELBASAN,
+    ffi::SCRIPT_GRANTHA, ffi::SCRIPT_KHOJKI, ffi::SCRIPT_KHUDAWADI,
+    ffi::SCRIPT_LINEAR_A, ffi::SCRIPT_MAHAJANI, ffi::SCRIPT_MANICHAEAN,
+    ffi::SCRIPT_MENDE_KIKAKUI, ffi::SCRIPT_MODI, ffi::SCRIPT_MRO,
+    ffi::SCRIPT_NABATAEAN, ffi::SCRIPT_OLD_NORTH_ARABIAN,
+    ffi::SCRIPT_OLD_PERMIC, ffi::SCRIPT_PAHAWH_HMONG, ffi::SCRIPT_PALMYRENE,
+    ffi::SCRIPT_PAU_CIN_HAU, ffi::SCRIPT_PSALTER_PAHLAVI, ffi::SCRIPT_SIDDHAM,
+    ffi::SCRIPT_TIRHUTA, ffi::SCRIPT_WARANG_CITI, ffi::SCRIPT_AHOM,
+    ffi::SCRIPT_ANATOLIAN_HIEROGLYPHS, ffi::SCRIPT_HATRAN, ffi::SCRIPT_MULTANI,
+    ffi::SCRIPT_OLD_HUNGARIAN, ffi::SCRIPT_SIGNWRITING, ffi::SCRIPT_ADLAM,
+    ffi::SCRIPT_BHAIKSUKI, ffi::SCRIPT_MARCHEN, ffi::SCRIPT_OSAGE,
+    ffi::SCRIPT_TANGUT, ffi::SCRIPT_NEWA, ffi::SCRIPT_MASARAM_GONDI,
+    ffi::SCRIPT_NUSHU, ffi::SCRIPT_SOYOMBO, ffi::SCRIPT_ZANABAZAR_SQUARE,
+    ffi::SCRIPT_DOGRA, ffi::SCRIPT_GUNJALA_GONDI, ffi::SCRIPT_HANIFI_ROHINGYA,
+    ffi::SCRIPT_MAKASAR, ffi::SCRIPT_MEDEFAIDRIN, ffi::SCRIPT_OLD_SOGDIAN,
+    ffi::SCRIPT_SOGDIAN,
+];
@@ -1,11 +1,47 @@
 use cffi::Ptr;
-use std::{slice, os::raw};
+use std::{
+    collections::BTreeMap,
+    io,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    os::raw,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
 
 use crate::*;
 
+/// A font face.
+///
+/// A face created from a [`Blob`] borrowing its data for `'a` can't outlive
+/// that data.
 #[repr(transparent)]
-pub struct Face(Ptr<ffi::hb_face_t>);
-impl_ptr!(Face, ffi::hb_face_t);
+pub struct Face<'a>(Ptr<ffi::hb_face_t>, PhantomData<&'a [u8]>);
+
+impl<'a> Deref for Face<'a> {
+    type Target = ffi::hb_face_t;
+
+    fn deref(&self) -> &ffi::hb_face_t {
+        unsafe { &*Ptr::as_ptr(&self.0) }
+    }
+}
+
+impl<'a> DerefMut for Face<'a> {
+    fn deref_mut(&mut self) -> &mut ffi::hb_face_t {
+        unsafe { &mut *Ptr::as_raw(&mut self.0) }
+    }
+}
+
+/// Cloning a face creates a new reference to the same underlying object,
+/// so for example one face can be shared by many fonts.
+impl<'a> Clone for Face<'a> {
+    fn clone(&self) -> Face<'a> {
+        unsafe {
+            let ptr = ffi::hb_face_reference(self.as_ptr() as *mut _);
+            Face(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+}
 
 impl cffi::Alloc for ffi::hb_face_t {
     fn free(this: *mut Self) {
@@ -13,27 +49,183 @@ impl cffi::Alloc for ffi::hb_face_t {
     }
 }
 
-/// Get number of faces on the blob
-pub fn number_in_blob(blob: Blob) -> usize {
-    unsafe { ffi::hb_face_count(blob.into_raw()) as usize }
+/// Source of tables for faces created with [`Face::from_provider()`].
+pub trait TableProvider {
+    /// Return the table identified by `tag`, or `None` if there is no such
+    /// table.
+    fn table(&self, tag: Tag) -> Option<Blob<'static>>;
+}
+
+extern "C" fn reference_table<P: TableProvider>(
+    _: *mut ffi::hb_face_t,
+    tag: Tag,
+    user_data: *mut raw::c_void,
+) -> *mut ffi::hb_blob_t {
+    let provider = unsafe { &*(user_data as *const P) };
+    // Unwinding into HarfBuzz is undefined behaviour, so a panicking provider
+    // is treated as if it had no table.
+    match panic::catch_unwind(AssertUnwindSafe(|| provider.table(tag))) {
+        Ok(Some(blob)) => blob.into_raw(),
+        _ => unsafe { ffi::hb_blob_get_empty() },
+    }
+}
+
+unsafe extern "C" fn destroy_provider<P>(user_data: *mut raw::c_void) {
+    let provider = Box::from_raw(user_data as *mut P);
+    let _ = panic::catch_unwind(AssertUnwindSafe(move || drop(provider)));
+}
+
+/// Faces in a font collection, such as a `.ttc` or `.otc` file.
+///
+/// Regular font files are treated as collections of a single face.
+pub struct Collection<'a> {
+    blob: Blob<'a>,
+    len: u32,
+}
+
+impl<'a> Collection<'a> {
+    /// Create a collection of faces in `blob`.
+    ///
+    /// The collection keeps a clone of `blob`, which makes `blob` immutable,
+    /// see [`Blob::clone()`].
+    pub fn new(blob: &Blob<'a>) -> Collection<'a> {
+        let blob = blob.clone();
+        let len = unsafe { ffi::hb_face_count(blob.as_ptr() as *mut _) };
+        Collection { blob, len }
+    }
+
+    /// Get number of faces in this collection.
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Open face number `index`, or return `None` if there is no such face.
+    pub fn face(&self, index: usize) -> Option<Face<'a>> {
+        if index < self.len() {
+            Some(Face::create(self.blob.clone(), index as u32))
+        } else {
+            None
+        }
+    }
+
+    /// Open all faces in this collection.
+    pub fn faces(&self) -> impl Iterator<Item = Face<'a>> + '_ {
+        (0..self.len).map(move |index| Face::create(self.blob.clone(), index))
+    }
 }
 
 // unimplemented:
 // - hb_face_set_user_data
 // - hb_face_get_user_data
-impl Face {
-    pub fn create(blob: Blob, index: u32) -> Face {
+impl Face<'static> {
+    pub fn empty() -> Face<'static> {
+        unsafe {
+            let ptr = ffi::hb_face_get_empty();
+            Face(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+
+    /// Create a face which loads its tables from `provider`.
+    ///
+    /// Tables are requested lazily, as HarfBuzz needs them, and may be
+    /// requested from any thread the face is used on.
+    pub fn from_provider<P>(provider: P) -> Face<'static>
+    where
+        P: TableProvider + Send + Sync + 'static,
+    {
+        let provider = Box::into_raw(Box::new(provider));
         unsafe {
-            let ptr = ffi::hb_face_create(blob.into_raw(), index);
-            Face(Ptr::from_raw(ptr))
+            // If this fails HarfBuzz destroys the provider and returns
+            // the empty face.
+            let ptr = ffi::hb_face_create_for_tables(
+                reference_table::<P>,
+                provider as *mut raw::c_void,
+                Some(destroy_provider::<P>),
+            );
+            Face(Ptr::from_raw(ptr), PhantomData)
         }
     }
 
-    pub fn empty() -> Face {
+    /// Load face number `index` from the font file at `path`.
+    ///
+    /// See [`Blob::from_path()`].
+    pub fn from_path(path: impl AsRef<Path>, index: u32)
+    -> io::Result<Face<'static>> {
+        Ok(Face::create(Blob::from_path(path)?, index))
+    }
+}
+
+impl<'a> Face<'a> {
+    pub fn create(mut blob: Blob<'a>, index: u32) -> Face<'a> {
         unsafe {
-            let ptr = ffi::hb_face_get_empty();
-            Face(Ptr::from_raw(ptr))
+            // hb_face_create takes its own reference to the blob.
+            let ptr = ffi::hb_face_create(blob.as_raw(), index);
+            Face(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+
+    /// Get a blob containing the table identified by `tag`.
+    ///
+    /// Returns `None` if this face has no such table. Since HarfBuzz doesn't
+    /// distinguish between missing and empty tables, `None` is also returned
+    /// for tables of length zero.
+    pub fn table(&self, tag: Tag) -> Option<Blob<'a>> {
+        let blob = unsafe {
+            let ptr = ffi::hb_face_reference_table(self.as_ptr() as *mut _, tag);
+            Blob::from_raw(ptr)
+        };
+        if blob.len() == 0 { None } else { Some(blob) }
+    }
+
+    /// Get a blob containing the entire font file backing this face.
+    ///
+    /// For faces created with [`FaceBuilder`] this compiles added tables into
+    /// a binary font file.
+    pub fn blob(&self) -> Blob<'a> {
+        unsafe {
+            let ptr = ffi::hb_face_reference_blob(self.as_ptr() as *mut _);
+            Blob::from_raw(ptr)
+        }
+    }
+
+    /// Take ownership of a face reference.
+    ///
+    /// ## Safety
+    ///
+    /// `ptr` must be a valid face whose data outlives `'a`.
+    pub(crate) unsafe fn from_raw(ptr: *mut ffi::hb_face_t) -> Face<'a> {
+        Face(Ptr::from_raw(ptr), PhantomData)
+    }
+
+    /// Make this face immutable, allowing it to be shared between threads.
+    ///
+    /// See [`FrozenFace`].
+    ///
+    /// Panics if this face loads its tables through FreeType, as FreeType
+    /// faces can't be used from many threads at once. Use
+    /// [`freeze_unchecked()`] to freeze such faces anyway.
+    pub fn freeze(self) -> FrozenFace<'a> {
+        if self.uses_freetype() {
+            panic!("Cannot freeze hb_face_t backed by FreeType");
         }
+        unsafe { self.freeze_unchecked() }
+    }
+
+    /// Make this face immutable without checking whether it's backed by
+    /// FreeType.
+    ///
+    /// ## Safety
+    ///
+    /// If this face was created with [`Face::from_ft_face()`], the FreeType
+    /// face must not be accessed from more than one thread at a time, neither
+    /// directly nor through this face.
+    pub unsafe fn freeze_unchecked(mut self) -> FrozenFace<'a> {
+        self.make_immutable();
+        FrozenFace(self)
     }
 
     pub fn into_raw(self) -> *mut ffi::hb_face_t {
@@ -49,6 +241,130 @@ impl Face {
     }
 }
 
+/// Builder for faces assembled from individual tables.
+///
+/// A built face can be compiled into a binary font file with
+/// [`Face::blob()`].
+pub struct FaceBuilder<'a> {
+    // Older versions of HarfBuzz keep all tables added to a builder, even
+    // with the same tag, so they are only passed to it once built.
+    tables: BTreeMap<Tag, Blob<'a>>,
+}
+
+impl<'a> FaceBuilder<'a> {
+    pub fn new() -> FaceBuilder<'a> {
+        FaceBuilder {
+            tables: BTreeMap::new(),
+        }
+    }
+
+    /// Add table for `tag` with data provided by `blob`, replacing any table
+    /// previously added for `tag`.
+    pub fn add_table(&mut self, tag: Tag, blob: Blob<'a>) {
+        self.tables.insert(tag, blob);
+    }
+
+    pub fn build(self) -> Face<'a> {
+        let mut face = unsafe {
+            let ptr = ffi::hb_face_builder_create();
+            Face(Ptr::from_raw(ptr), PhantomData)
+        };
+        for (tag, mut blob) in self.tables {
+            // hb_face_builder_add_table takes its own reference to the blob.
+            let r = unsafe {
+                ffi::hb_face_builder_add_table(face.as_raw(), tag, blob.as_raw())
+            };
+            if r == 0 {
+                panic!("allocation failed");
+            }
+        }
+        face
+    }
+}
+
+impl<'a> Default for FaceBuilder<'a> {
+    fn default() -> FaceBuilder<'a> {
+        FaceBuilder::new()
+    }
+}
+
+/// An immutable face, created with [`Face::freeze()`].
+///
+/// Once made immutable HarfBuzz objects are safe to use from many threads
+/// at once. Unlike [`Face`] this type only gives shared access to the
+/// underlying [`hb_face_t`], so methods which change a face, such as
+/// [`set_index()`], [`set_upem()`] and [`set_glyph_count()`], are not
+/// available on it.
+///
+/// Cloning a frozen face creates a new reference to the same underlying
+/// object.
+///
+/// Faces backed by FreeType can only be frozen with
+/// [`Face::freeze_unchecked()`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct FrozenFace<'a>(Face<'a>);
+
+unsafe impl<'a> Send for FrozenFace<'a> {}
+unsafe impl<'a> Sync for FrozenFace<'a> {}
+
+impl<'a> Deref for FrozenFace<'a> {
+    type Target = ffi::hb_face_t;
+
+    fn deref(&self) -> &ffi::hb_face_t {
+        &self.0
+    }
+}
+
+impl<'a> FrozenFace<'a> {
+    /// See [`Face::table()`].
+    pub fn table(&self, tag: Tag) -> Option<Blob<'a>> {
+        self.0.table(tag)
+    }
+
+    /// See [`Face::blob()`].
+    pub fn blob(&self) -> Blob<'a> {
+        self.0.blob()
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_face_t {
+        self.0.as_ptr()
+    }
+}
+
+/// Iterator over tags of tables in a face, returned by
+/// [`hb_face_t::table_tags_iter()`].
+pub struct TableTags<'f> {
+    face: &'f ffi::hb_face_t,
+    offset: u32,
+    buf: [Tag; 16],
+    pos: usize,
+    len: usize,
+}
+
+impl<'f> Iterator for TableTags<'f> {
+    type Item = Tag;
+
+    fn next(&mut self) -> Option<Tag> {
+        if self.pos == self.len {
+            let mut len = self.buf.len() as raw::c_uint;
+            unsafe {
+                ffi::hb_face_get_table_tags(
+                    self.face, self.offset, &mut len, self.buf.as_mut_ptr());
+            }
+            if len == 0 {
+                return None;
+            }
+            self.offset += len;
+            self.pos = 0;
+            self.len = len as usize;
+        }
+        let tag = self.buf[self.pos];
+        self.pos += 1;
+        Some(tag)
+    }
+}
+
 impl ffi::hb_face_t {
     pub fn is_immutable(&self) -> bool {
         unsafe { ffi::hb_face_is_immutable(self) != 0 }
@@ -82,43 +398,184 @@ impl ffi::hb_face_t {
         unsafe { ffi::hb_face_set_glyph_count(self, count) }
     }
 
+    /// Fill `buf` with tags of the first tables in this face, returning
+    /// the filled part of it. See [`table_tags_iter()`] to get all tags.
     pub fn table_tags<'buf>(&self, buf: &'buf mut [Tag]) -> &'buf mut [Tag] {
         let mut len = buf.len() as raw::c_uint;
-        let count = unsafe {
-            ffi::hb_face_get_table_tags(self, 0, &mut len, buf.as_mut_ptr())
+        unsafe {
+            ffi::hb_face_get_table_tags(self, 0, &mut len, buf.as_mut_ptr());
+        }
+        &mut buf[..len as usize]
+    }
+
+    /// Iterate over tags of all tables in this face.
+    ///
+    /// Faces not backed by a font file, such as those created with
+    /// [`Face::from_provider()`], have no table directory and this iterator
+    /// will be empty for them.
+    pub fn table_tags_iter(&self) -> TableTags<'_> {
+        TableTags {
+            face: self,
+            offset: 0,
+            buf: [Tag::new([0; 4]); 16],
+            pos: 0,
+            len: 0,
+        }
+    }
+
+    /// Check whether this face has a non-empty table identified by `tag`.
+    ///
+    /// This works for all faces, including those without a table directory,
+    /// such as faces created with [`Face::from_provider()`] or [`FaceBuilder`].
+    pub fn has_table(&self, tag: Tag) -> bool {
+        let blob = unsafe {
+            Blob::from_raw(ffi::hb_face_reference_table(self.query_ptr(), tag))
         };
-        &mut buf[..count as usize]
+        blob.len() != 0
+    }
+
+    /// Pointer to this face for passing to HarfBuzz's query functions.
+    ///
+    /// XXX: HarfBuzz declares them as taking a mutable face, but they only
+    /// read from it, and are safe to call concurrently on an immutable face.
+    fn query_ptr(&self) -> *mut ffi::hb_face_t {
+        self as *const ffi::hb_face_t as *mut ffi::hb_face_t
     }
 
-    /// Populate `out` with all Unicode codepoints covered by this face.
-    pub fn collect_unicodes(&mut self, out: &mut ffi::hb_set_t) {
-        unsafe { ffi::hb_face_collect_unicodes(self, out) }
+    /// Return all Unicode codepoints covered by this face.
+    pub fn unicodes(&self) -> Set {
+        let mut set = Set::new();
+        unsafe { ffi::hb_face_collect_unicodes(self.query_ptr(), set.as_raw()) }
+        set
+    }
+
+    /// Return all variation selector codepoints covered by this face.
+    pub fn variation_selectors(&self) -> Set {
+        let mut set = Set::new();
+        unsafe {
+            ffi::hb_face_collect_variation_selectors(self.query_ptr(), set.as_raw())
+        }
+        set
     }
 
-    /// Populate `out` with variation selector codepoints covered by this face.
-    pub fn variant_selectors(&mut self, out: &mut ffi::hb_set_t) {
-        unsafe { ffi::hb_face_collect_variation_selectors(self, out) }
+    /// Return all Unicode codepoints for which this face has a variant
+    /// under `selector`.
+    pub fn variation_unicodes(&self, selector: Codepoint) -> Set {
+        let mut set = Set::new();
+        unsafe {
+            ffi::hb_face_collect_variation_unicodes(
+                self.query_ptr(), selector, set.as_raw())
+        }
+        set
     }
 
-    pub fn variation_unicodes(&mut self, selector: Codepoint, out: &mut ffi::hb_set_t) {
-        unsafe { ffi::hb_face_collect_variation_unicodes(self, selector, out) }
+    /// Check which characters of `text` this face has glyphs for.
+    ///
+    /// Only the face's character map is consulted, so characters which would
+    /// be composed or decomposed during shaping are reported as they appear
+    /// in `text`.
+    ///
+    /// This collects the whole character map with [`unicodes()`] on each
+    /// call. To check many strings against the same face, collect it once
+    /// and use [`Coverage::new()`] instead.
+    ///
+    /// [`unicodes()`]: Self::unicodes()
+    pub fn covers(&self, text: &str) -> Coverage {
+        Coverage::new(&self.unicodes(), text)
+    }
+}
+
+/// Report of which characters of a string a face covers, returned by
+/// [`hb_face_t::covers()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Coverage {
+    total: usize,
+    missing: Vec<(usize, char)>,
+}
+
+impl Coverage {
+    /// Check which characters of `text` are in `unicodes`, usually
+    /// the character map of a face collected with [`hb_face_t::unicodes()`].
+    pub fn new(unicodes: &Set, text: &str) -> Coverage {
+        let missing = text.char_indices()
+            .filter(|&(_, chr)| !unicodes.contains(chr as Codepoint))
+            .collect();
+        Coverage {
+            total: text.chars().count(),
+            missing,
+        }
+    }
+
+    /// Check whether the face covers all characters.
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+
+    /// Return the number of characters the face covers.
+    pub fn covered(&self) -> usize {
+        self.total - self.missing.len()
+    }
+
+    /// Return characters the face doesn't cover, together with their byte
+    /// offsets in the string.
+    pub fn missing(&self) -> &[(usize, char)] {
+        &self.missing
     }
 }
 
 #[cfg(feature = "freetype")]
 use freetype;
 
+/// Marks faces created with [`Face::from_ft_face()`]. Only the address of
+/// this static matters, HarfBuzz uses it as a user data key.
+#[cfg(feature = "freetype")]
+static FT_FACE_KEY: u8 = 0;
+
 #[cfg(feature = "freetype")]
-impl Face {
-    pub fn from_ft_face(face: &mut freetype::Face) -> Face {
+impl Face<'static> {
+    /// Create a face which loads its tables through `face`.
+    ///
+    /// Since `face` remains usable, such faces can't be frozen with
+    /// [`Face::freeze()`].
+    pub fn from_ft_face(face: &mut freetype::Face) -> Face<'static> {
         unsafe {
             freetype::ffi::FT_Reference_Face(face.raw_mut());
             let ptr = ffi::hb_ft_face_create_referenced(face.raw_mut());
-            Face(Ptr::from_raw(ptr))
+            let r = ffi::hb_face_set_user_data(
+                ptr,
+                &FT_FACE_KEY as *const u8 as *mut ffi::hb_user_data_key_t,
+                &FT_FACE_KEY as *const u8 as *mut raw::c_void,
+                None,
+                1,
+            );
+            let face = Face(Ptr::from_raw(ptr), PhantomData);
+            if r == 0 {
+                panic!("allocation failed");
+            }
+            face
         }
     }
 }
 
+impl ffi::hb_face_t {
+    /// Check whether this face was created with [`Face::from_ft_face()`].
+    #[cfg(feature = "freetype")]
+    pub(crate) fn uses_freetype(&self) -> bool {
+        let data = unsafe {
+            ffi::hb_face_get_user_data(
+                self.query_ptr(),
+                &FT_FACE_KEY as *const u8 as *mut ffi::hb_user_data_key_t,
+            )
+        };
+        !data.is_null()
+    }
+
+    #[cfg(not(feature = "freetype"))]
+    pub(crate) fn uses_freetype(&self) -> bool {
+        false
+    }
+}
+
 #[cfg(feature = "freetype")]
 impl ffi::hb_font_t {
     pub fn ft_face(&mut self) -> freetype::ffi::FT_Face {
@@ -0,0 +1,314 @@
+use std::ops::Range;
+
+use crate::*;
+
+/// Shapes text with an ordered list of fonts, using each next font for parts
+/// of the text the previous ones have no glyphs for.
+pub struct FallbackShaper<'a> {
+    fonts: Vec<Font<'a>>,
+}
+
+/// A positioned glyph produced by [`FallbackShaper`].
+#[derive(Clone, Copy, Debug)]
+pub struct ShapedGlyph {
+    /// Index of the font this glyph came from.
+    pub font: usize,
+    /// Glyph index in that font.
+    pub glyph: Codepoint,
+    /// Byte offset in the shaped text of the cluster this glyph belongs to.
+    pub cluster: u32,
+    /// Position of this glyph, in units of the first font.
+    ///
+    /// Positions from other fonts are multiplied by the first font's scale
+    /// divided by their font's scale, so that glyphs from all fonts can be
+    /// laid out together as if all fonts were set to the same size.
+    pub position: GlyphPosition,
+}
+
+impl<'a> FallbackShaper<'a> {
+    /// Create a new shaper over `fonts`, in order of preference.
+    ///
+    /// Panics if `fonts` is empty.
+    pub fn new(fonts: Vec<Font<'a>>) -> FallbackShaper<'a> {
+        if fonts.is_empty() {
+            panic!("FallbackShaper needs at least one font");
+        }
+        FallbackShaper { fonts }
+    }
+
+    pub fn fonts(&self) -> &[Font<'a>] {
+        &self.fonts
+    }
+
+    /// Shape `text`, returning glyphs in visual order.
+    ///
+    /// Segment properties are guessed from the text. When a font lacks glyphs
+    /// for some clusters those clusters are shaped again with the next font,
+    /// with the rest of `text` passed as context and segment properties
+    /// guessed again from just those clusters, so that for example an Arabic
+    /// word in Latin text is shaped right to left with Arabic joining.
+    /// Clusters containing only characters common to many scripts, such as
+    /// digits and punctuation, keep properties of the text around them.
+    ///
+    /// Characters no font has glyphs for are left as `.notdef` glyphs of
+    /// the last font.
+    ///
+    /// `text` should be a single paragraph, or part of it, in which
+    /// the primary font's glyphs are laid out in one direction.
+    pub fn shape(&self, text: &str, features: &[Feature]) -> Vec<ShapedGlyph> {
+        if text.is_empty() {
+            return Vec::new();
+        }
+
+        let mut glyphs = Vec::with_capacity(text.len());
+        self.shape_range(0, text, 0..text.len(), None, features, &mut glyphs);
+        glyphs
+    }
+
+    fn shape_range(
+        &self,
+        font: usize,
+        text: &str,
+        range: Range<usize>,
+        outer: Option<SegmentProperties>,
+        features: &[Feature],
+        out: &mut Vec<ShapedGlyph>,
+    ) {
+        let mut buffer = UnicodeBuffer::new();
+        buffer.add_utf8(text, range.clone());
+        buffer.guess_segment_properties();
+        // Script is only left unset if there were no characters specific
+        // to any script.
+        if let Some(outer) = outer {
+            if buffer.script() == ffi::SCRIPT_INVALID {
+                buffer.set_segment_properties(outer);
+            }
+        }
+        let props = buffer.segment_properties();
+        let buffer = buffer.shape(&self.fonts[font], features);
+
+        let infos = buffer.infos();
+        let positions = buffer.positions();
+        let has_fallback = font + 1 < self.fonts.len();
+        let scale = self.scale_to_first(font);
+
+        for run in split_runs(infos, range, has_fallback) {
+            match run {
+                Run::Keep(glyphs) => {
+                    out.extend(infos[glyphs.clone()].iter().zip(&positions[glyphs])
+                        .map(|(info, position)| ShapedGlyph {
+                            font,
+                            glyph: info.codepoint,
+                            cluster: info.cluster,
+                            position: scale(*position),
+                        }));
+                }
+                Run::Fallback(sub_range) => {
+                    self.shape_range(
+                        font + 1, text, sub_range, Some(props), features, out);
+                }
+            }
+        }
+    }
+
+    /// Return function converting positions in units of font `font` to units
+    /// of the first font.
+    fn scale_to_first(&self, font: usize) -> impl Fn(GlyphPosition) -> GlyphPosition {
+        let (to_x, to_y) = self.fonts[0].scale();
+        let (from_x, from_y) = self.fonts[font].scale();
+        let convert = |value: Position, to: i32, from: i32| {
+            if to == from || from == 0 {
+                value
+            } else {
+                (i64::from(value) * i64::from(to) / i64::from(from)) as Position
+            }
+        };
+        move |mut position| {
+            position.x_advance = convert(position.x_advance, to_x, from_x);
+            position.x_offset = convert(position.x_offset, to_x, from_x);
+            position.y_advance = convert(position.y_advance, to_y, from_y);
+            position.y_offset = convert(position.y_offset, to_y, from_y);
+            position
+        }
+    }
+}
+
+/// Part of glyphs shaped with one font, see [`split_runs()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Run {
+    /// Keep glyphs in this range of the shaped buffer.
+    Keep(Range<usize>),
+    /// Shape this range of text again with the next font.
+    Fallback(Range<usize>),
+}
+
+/// Split glyphs shaped from `range` of text into runs, in visual order, of
+/// glyphs to keep and of text to shape again with the next font.
+///
+/// Each run of adjacent clusters missing any glyph becomes a single
+/// [`Run::Fallback`], unless there is no font to fall back to.
+fn split_runs(infos: &[GlyphInfo], range: Range<usize>, has_fallback: bool)
+-> Vec<Run> {
+    if infos.is_empty() {
+        return Vec::new();
+    }
+    if !has_fallback {
+        return vec![Run::Keep(0..infos.len())];
+    }
+
+    // Sorted cluster values, to find where in the text each cluster ends.
+    let mut clusters = infos.iter().map(|info| info.cluster).collect::<Vec<_>>();
+    clusters.sort_unstable();
+    clusters.dedup();
+    let cluster_end = |cluster: u32| match clusters.binary_search(&cluster) {
+        Ok(inx) if inx + 1 < clusters.len() => clusters[inx + 1] as usize,
+        _ => range.end,
+    };
+    let is_missing = |glyphs: &[GlyphInfo]| glyphs.iter().any(|info| info.codepoint == 0);
+
+    let mut runs = Vec::new();
+    let mut start = 0;
+    while start < infos.len() {
+        let end = cluster_run_end(infos, start);
+
+        if !is_missing(&infos[start..end]) {
+            match runs.last_mut() {
+                Some(Run::Keep(glyphs)) => glyphs.end = end,
+                _ => runs.push(Run::Keep(start..end)),
+            }
+            start = end;
+            continue;
+        }
+
+        // Extend the run over all following clusters which are also
+        // missing glyphs, so that it's shaped again as a whole.
+        let mut run_end = end;
+        while run_end < infos.len() {
+            let next = cluster_run_end(infos, run_end);
+            if !is_missing(&infos[run_end..next]) {
+                break;
+            }
+            run_end = next;
+        }
+
+        let run = &infos[start..run_end];
+        let first = run.iter().map(|info| info.cluster).min().unwrap();
+        let last = run.iter().map(|info| info.cluster).max().unwrap();
+        runs.push(Run::Fallback(first as usize..cluster_end(last)));
+        start = run_end;
+    }
+    runs
+}
+
+/// Return index one past the last glyph of the cluster starting at `start`.
+fn cluster_run_end(infos: &[GlyphInfo], start: usize) -> usize {
+    let cluster = infos[start].cluster;
+    infos[start..].iter()
+        .position(|info| info.cluster != cluster)
+        .map_or(infos.len(), |len| start + len)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::mem;
+
+    use super::*;
+
+    /// Create glyph infos from `(glyph, cluster)` pairs.
+    fn infos(glyphs: &[(Codepoint, u32)]) -> Vec<GlyphInfo> {
+        glyphs.iter()
+            .map(|&(glyph, cluster)| {
+                let mut info: GlyphInfo = unsafe { mem::zeroed() };
+                info.codepoint = glyph;
+                info.cluster = cluster;
+                info
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_glyphs() {
+        assert_eq!(split_runs(&[], 0..0, true), vec![]);
+        assert_eq!(split_runs(&[], 0..0, false), vec![]);
+    }
+
+    #[test]
+    fn all_glyphs_present() {
+        let infos = infos(&[(5, 0), (6, 1), (7, 2)]);
+        assert_eq!(split_runs(&infos, 0..3, true), vec![Run::Keep(0..3)]);
+    }
+
+    #[test]
+    fn missing_cluster_ltr() {
+        let infos = infos(&[(5, 0), (0, 1), (6, 2)]);
+        assert_eq!(split_runs(&infos, 0..3, true), vec![
+            Run::Keep(0..1),
+            Run::Fallback(1..2),
+            Run::Keep(2..3),
+        ]);
+    }
+
+    #[test]
+    fn adjacent_missing_clusters_are_merged() {
+        let infos = infos(&[(5, 0), (0, 1), (0, 3), (6, 4)]);
+        assert_eq!(split_runs(&infos, 0..5, true), vec![
+            Run::Keep(0..1),
+            Run::Fallback(1..4),
+            Run::Keep(3..4),
+        ]);
+    }
+
+    #[test]
+    fn missing_last_cluster_extends_to_range_end() {
+        let infos = infos(&[(5, 0), (6, 1), (0, 3)]);
+        assert_eq!(split_runs(&infos, 0..6, true), vec![
+            Run::Keep(0..2),
+            Run::Fallback(3..6),
+        ]);
+    }
+
+    #[test]
+    fn missing_clusters_rtl() {
+        // Glyphs of right-to-left text are in visual order, so clusters
+        // decrease.
+        let infos = infos(&[(5, 6), (0, 4), (0, 2), (7, 0)]);
+        assert_eq!(split_runs(&infos, 0..8, true), vec![
+            Run::Keep(0..1),
+            Run::Fallback(2..6),
+            Run::Keep(3..4),
+        ]);
+    }
+
+    #[test]
+    fn missing_first_cluster_rtl() {
+        let infos = infos(&[(0, 2), (7, 0)]);
+        assert_eq!(split_runs(&infos, 0..4, true), vec![
+            Run::Fallback(2..4),
+            Run::Keep(1..2),
+        ]);
+    }
+
+    #[test]
+    fn cluster_with_some_glyphs_missing() {
+        let infos = infos(&[(5, 0), (0, 0), (6, 2)]);
+        assert_eq!(split_runs(&infos, 0..3, true), vec![
+            Run::Fallback(0..2),
+            Run::Keep(2..3),
+        ]);
+    }
+
+    #[test]
+    fn clusters_are_offsets_in_whole_text() {
+        let infos = infos(&[(0, 3), (5, 6)]);
+        assert_eq!(split_runs(&infos, 3..9, true), vec![
+            Run::Fallback(3..6),
+            Run::Keep(1..2),
+        ]);
+    }
+
+    #[test]
+    fn no_fallback_font() {
+        let infos = infos(&[(5, 0), (0, 1), (6, 2)]);
+        assert_eq!(split_runs(&infos, 0..3, false), vec![Run::Keep(0..3)]);
+    }
+}
@@ -9,7 +9,9 @@
 
 use std::{
     fmt,
+    hash::{Hash, Hasher},
     os::raw::*,
+    ptr,
 };
 
 use crate::ffi::{
@@ -53,6 +55,40 @@ impl fmt::Debug for hb_glyph_info_t {
     }
 }
 
+bitflags! {
+    /// Flags for [`hb_glyph_info_t`], as returned by
+    /// [`hb_glyph_info_get_glyph_flags()`].
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[repr(transparent)]
+    pub struct hb_glyph_flags_t: c_int {
+        /// Indicates that if input text is broken at the beginning of
+        /// the cluster this glyph is part of, then both sides need to be
+        /// re-shaped, as the result might be different. On the flip side,
+        /// it means that when this flag is not present, then it is safe
+        /// to break the glyph-run at the beginning of this cluster, and
+        /// the two sides will represent the exact same result one would get
+        /// if breaking input text at the beginning of this cluster and
+        /// shaping the two sides separately.
+        const UNSAFE_TO_BREAK = 0x0001;
+        /// Indicates that if input text is changed on one side of
+        /// the beginning of the cluster this glyph is part of, then
+        /// the shaping results for the other side might change. Note that
+        /// the absence of this flag will NOT by itself mean that it IS safe
+        /// to concat text. Only two pieces of text both of which clear of this
+        /// flag can be concatenated safely. This flag is only produced when
+        /// [`BUFFER_FLAG_PRODUCE_UNSAFE_TO_CONCAT`] is set on the buffer.
+        #[cfg(hb_4_0)]
+        const UNSAFE_TO_CONCAT = 0x0002;
+        /// In scripts that use elongation (Arabic, Mongolian, Syriac, etc.),
+        /// this flag signifies that it is safe to insert a U+0640 TATWEEL
+        /// character before this cluster for elongation. This flag is only
+        /// produced when [`BUFFER_FLAG_PRODUCE_SAFE_TO_INSERT_TATWEEL`] is
+        /// set on the buffer.
+        #[cfg(hb_5_1)]
+        const SAFE_TO_INSERT_TATWEEL = 0x0004;
+    }
+}
+
 /// The [`hb_glyph_position_t`] is the structure that holds the positions of
 /// the glyph in both horizontal and vertical directions. All positions
 /// in `hb_glyph_position_t` are relative to the current point.
@@ -101,7 +137,23 @@ pub struct hb_segment_properties_t {
     _reserved2: *mut c_void,
 }
 
-/*impl PartialEq for hb_segment_properties_t {
+impl hb_segment_properties_t {
+    pub fn new(
+        direction: hb_direction_t,
+        script: hb_script_t,
+        language: hb_language_t,
+    ) -> hb_segment_properties_t {
+        hb_segment_properties_t {
+            direction,
+            script,
+            language,
+            _reserved1: ptr::null_mut(),
+            _reserved2: ptr::null_mut(),
+        }
+    }
+}
+
+impl PartialEq for hb_segment_properties_t {
     fn eq(&self, other: &hb_segment_properties_t) -> bool {
         unsafe {
             hb_segment_properties_equal(self, other) != 0
@@ -111,6 +163,12 @@ pub struct hb_segment_properties_t {
 
 impl Eq for hb_segment_properties_t {}
 
+impl Hash for hb_segment_properties_t {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        unsafe { hb_segment_properties_hash(self) }.hash(state)
+    }
+}
+
 impl fmt::Debug for hb_segment_properties_t {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("hb_segment_properties_t")
@@ -119,11 +177,16 @@ impl fmt::Debug for hb_segment_properties_t {
             .field("language", &self.language)
             .finish()
     }
-}*/
+}
 
 #[repr(C)]
 pub struct hb_buffer_t(cffi::Private);
 
+/// The default code point for replacing invalid characters in a given
+/// encoding, U+FFFD REPLACEMENT CHARACTER.
+pub const HB_BUFFER_REPLACEMENT_CODEPOINT_DEFAULT: hb_codepoint_t = 0xfffd;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[repr(C)]
 pub enum hb_buffer_content_type_t {
     INVALID = 0,
@@ -132,7 +195,8 @@ pub enum hb_buffer_content_type_t {
 }
 
 bitflags! {
-    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[repr(transparent)]
     pub struct hb_buffer_flags_t: c_int {
         /// The default buffer flag.
         const BUFFER_FLAG_DEFAULT = 0x0000;
@@ -156,10 +220,32 @@ bitflags! {
         /// zeroing the advance width.)  This flag takes precedence over
         /// [`HB_BUFFER_FLAG_REMOVE_DEFAULT_IGNORABLES`].
         const BUFFER_FLAG_REMOVE_DEFAULT_IGNORABLES = 0x0008;
+        /// Flag indicating that a dotted circle should not be inserted in the
+        /// rendering of incorrect character sequences (such at `<0905 093E>`).
+        #[cfg(hb_2_4)]
+        const BUFFER_FLAG_DO_NOT_INSERT_DOTTED_CIRCLE = 0x0010;
+        /// Flag indicating that the [`hb_shape()`] call and its variants
+        /// should perform various verification processes on the results of
+        /// the shaping operation on the buffer. If the verification fails,
+        /// then either a buffer message is sent, if a message handler is
+        /// installed on the buffer, or a message is written to standard error.
+        /// In either case, the shaping result might be modified to show
+        /// the failed output.
+        #[cfg(hb_3_4)]
+        const BUFFER_FLAG_VERIFY = 0x0020;
+        /// Flag indicating that the `UNSAFE_TO_CONCAT` glyph-flag should be
+        /// produced by the shaper. By default it will not be produced since
+        /// it incurs a cost.
+        #[cfg(hb_4_0)]
+        const BUFFER_FLAG_PRODUCE_UNSAFE_TO_CONCAT = 0x0040;
+        /// Flag indicating that the `SAFE_TO_INSERT_TATWEEL` glyph-flag should
+        /// be produced by the shaper. By default it will not be produced.
+        #[cfg(hb_5_1)]
+        const BUFFER_FLAG_PRODUCE_SAFE_TO_INSERT_TATWEEL = 0x0080;
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[repr(C)]
 pub enum hb_buffer_cluster_level_t {
     MONOTONE_GRAPHEMES = 0,
@@ -167,16 +253,17 @@ pub enum hb_buffer_cluster_level_t {
     CHARACTERS = 2,
 }
 
-/*impl Default for hb_buffer_cluster_level_t {
+impl Default for hb_buffer_cluster_level_t {
     fn default() -> Self {
         hb_buffer_cluster_level_t::MONOTONE_GRAPHEMES
     }
-}*/
+}
 
 bitflags! {
     /// Flags that control what glyph information are serialized
     /// in [`hb_buffer_serialize_glyphs()`].
-    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[repr(transparent)]
     pub struct hb_buffer_serialize_flags_t: c_int {
         /// Serialize glyph names, clusters and positions.
         const DEFAULT = 0x0000;
@@ -200,10 +287,12 @@ bitflags! {
 pub enum hb_buffer_serialize_format_t {
   TEXT = 0x54455854,
   JSON = 0x4a534f4e,
+  INVALID = 0,
 }
 
 bitflags! {
-    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+    #[repr(transparent)]
     pub struct hb_buffer_diff_flags_t: c_int { /*< flags >*/
         const EQUAL = 0x0000;
         /// Buffers with different content type cannot be meaningfully compared
@@ -223,7 +312,7 @@ bitflags! {
         const CODEPOINT_MISMATCH = 0x0010;
         const CLUSTER_MISMATCH = 0x0020;
         const GLYPH_FLAGS_MISMATCH = 0x0040;
-        const POSITION_MISMATCH = 0x008;
+        const POSITION_MISMATCH = 0x0080;
     }
 }
 
@@ -235,6 +324,9 @@ pub type hb_buffer_message_func_t = extern "C" fn(
 ) -> hb_bool_t;
 
 extern "C" {
+    /// Returns glyph flags encoded within a [`hb_glyph_info_t`].
+    pub fn hb_glyph_info_get_glyph_flags(info: *const hb_glyph_info_t) -> hb_glyph_flags_t;
+
     /// Checks the equality of two [`hb_segment_properties_t`]'s.
     pub fn hb_segment_properties_equal(
         a: *const hb_segment_properties_t,
@@ -288,14 +380,14 @@ extern "C" {
         content_type: hb_buffer_content_type_t,
     );
 
-    pub fn hb_buffer_get_content_type(buffer: *mut hb_buffer_t) -> hb_buffer_content_type_t;
+    pub fn hb_buffer_get_content_type(buffer: *const hb_buffer_t) -> hb_buffer_content_type_t;
 
     pub fn hb_buffer_set_unicode_funcs(
         buffer: *mut hb_buffer_t,
         functs: *mut hb_unicode_funcs_t,
     );
 
-    pub fn hb_buffer_get_unicode_funcs(buffer: *mut hb_buffer_t) -> *mut hb_unicode_funcs_t;
+    pub fn hb_buffer_get_unicode_funcs(buffer: *const hb_buffer_t) -> *mut hb_unicode_funcs_t;
 
     /// Set the text flow direction of the buffer. No shaping can happen without
     /// setting `buffer` direction, and it controls the visual direction for the
@@ -306,7 +398,7 @@ extern "C" {
     /// and shaping with RTL direction.
     pub fn hb_buffer_set_direction(buffer: *mut hb_buffer_t, direction: hb_direction_t);
 
-    pub fn hb_buffer_get_direction(buffer: &mut hb_buffer_t) -> hb_direction_t;
+    pub fn hb_buffer_get_direction(buffer: *const hb_buffer_t) -> hb_direction_t;
 
     /// Sets the script of `buffer` to `script`.
     ///
@@ -319,7 +411,7 @@ extern "C" {
     /// the corresponding script from an ISO 15924 script tag.
     pub fn hb_buffer_set_script(buffer: *mut hb_buffer_t, script: hb_script_t);
 
-    pub fn hb_buffer_get_script(buffer: *mut hb_buffer_t) -> hb_script_t;
+    pub fn hb_buffer_get_script(buffer: *const hb_buffer_t) -> hb_script_t;
 
     /// Sets the language of `buffer` to `language`.
     ///
@@ -332,7 +424,7 @@ extern "C" {
     /// to [`hb_language_t`].
     pub fn hb_buffer_set_language(buffer: *mut hb_buffer_t, language: hb_language_t);
 
-    pub fn hb_buffer_get_language(buffer: *mut hb_buffer_t) -> hb_language_t;
+    pub fn hb_buffer_get_language(buffer: *const hb_buffer_t) -> hb_language_t;
 
     /// Sets the segment properties of the buffer, a shortcut for calling
     /// [`hb_buffer_set_direction()`], [`hb_buffer_set_script()`] and
@@ -343,7 +435,7 @@ extern "C" {
     );
 
     pub fn hb_buffer_get_segment_properties(
-        buffer: &mut hb_buffer_t,
+        buffer: *const hb_buffer_t,
         props: *mut hb_segment_properties_t,
     );
 
@@ -370,14 +462,14 @@ extern "C" {
     /// Sets `buffer` flags to `flags`. See [`hb_buffer_flags_t`].
     pub fn hb_buffer_set_flags(buffer: *mut hb_buffer_t, flags: hb_buffer_flags_t);
 
-    pub fn hb_buffer_get_flags(buffer: *mut hb_buffer_t) -> hb_buffer_flags_t;
+    pub fn hb_buffer_get_flags(buffer: *const hb_buffer_t) -> hb_buffer_flags_t;
 
     pub fn hb_buffer_set_cluster_level(
         buffer: *mut hb_buffer_t,
         cluster_level: hb_buffer_cluster_level_t,
     );
 
-    pub fn hb_buffer_get_cluster_level(buffer: *mut hb_buffer_t) -> hb_buffer_cluster_level_t;
+    pub fn hb_buffer_get_cluster_level(buffer: *const hb_buffer_t) -> hb_buffer_cluster_level_t;
 
     /// Sets the [`hb_codepoint_t`] that replaces invalid entries for a given
     /// encoding when adding text to `buffer`.
@@ -385,7 +477,7 @@ extern "C" {
     /// Default is [`HB_BUFFER_REPLACEMENT_CODEPOINT_DEFAULT`].
     pub fn hb_buffer_set_replacement_codepoint(buffer: *mut hb_buffer_t, replacement: hb_codepoint_t);
 
-    pub fn hb_buffer_get_replacement_codepoint(buffer: *mut hb_buffer_t) -> hb_codepoint_t;
+    pub fn hb_buffer_get_replacement_codepoint(buffer: *const hb_buffer_t) -> hb_codepoint_t;
 
     /// Resets the buffer to its initial status, as if it was just newly created
     /// with [`hb_buffer_create()`].
@@ -513,7 +605,7 @@ extern "C" {
     pub fn hb_buffer_set_length(buffer: *mut hb_buffer_t, length: c_uint) -> hb_bool_t;
 
     /// Returns the number of items in the buffer.
-    pub fn hb_buffer_get_length(buffer: *mut hb_buffer_t) -> c_uint;
+    pub fn hb_buffer_get_length(buffer: *const hb_buffer_t) -> c_uint;
 
     /// Returns `buffer` glyph information array. Returned pointer  is valid as
     /// long as `buffer` contents are not modified.
@@ -543,7 +635,7 @@ extern "C" {
 
     pub fn hb_buffer_serialize_format_to_string(format: hb_buffer_serialize_format_t) -> *const c_char;
 
-    pub fn hb_buffer_serialize_list_formats() -> *const *const c_char;
+    pub fn hb_buffer_serialize_list_formats() -> *mut *const c_char;
 
     pub fn hb_buffer_serialize_glyphs(
         buffer: *mut hb_buffer_t,
@@ -551,7 +643,7 @@ extern "C" {
         end: c_uint,
         buf: *mut c_char,
         buf_size: c_uint,
-        buf_consumed: *mut c_char,
+        buf_consumed: *mut c_uint,
         font: *mut hb_font_t,
         format: hb_buffer_serialize_format_t,
         flags: hb_buffer_serialize_flags_t,
@@ -561,7 +653,7 @@ extern "C" {
         buffer: *mut hb_buffer_t,
         buf: *const c_char,
         buf_len: c_int,
-        end_ptr: *const *const c_char,
+        end_ptr: *mut *const c_char,
         font: *mut hb_font_t,
         format: hb_buffer_serialize_format_t,
     ) -> hb_bool_t;
@@ -2,7 +2,12 @@
 
 #![allow(non_camel_case_types)]
 
-use std::os::raw::*;
+use std::{
+    ffi::CStr,
+    fmt,
+    os::raw::*,
+    ptr,
+};
 
 pub type hb_bool_t = c_int;
 pub type hb_codepoint_t = u32;
@@ -20,7 +25,9 @@ pub union hb_var_int_t {
     i8: [i8; 4],
 }
 
-#[derive(Clone, Copy)]
+/// Tags are ordered by their bytes, the same way OpenType orders them
+/// in a font's table directory.
+#[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
 #[repr(transparent)]
 pub struct hb_tag_t(u32);
 
@@ -35,30 +42,28 @@ impl hb_tag_t {
         )
     }
 
-    /*pub fn from_string(str: &str) -> hb_tag_t {
-        let bytes = str.as_bytes();
-        unsafe {
-            hb_tag_from_string(bytes.as_ptr() as *const c_char, bytes.len() as c_int)
-        }
-    }*/
+    /// Return the four bytes of this tag.
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; 4] {
+        self.0.to_be_bytes()
+    }
 }
 
-/*impl fmt::Debug for hb_tag_t {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let mut buf = [0u8; 4];
-        unsafe {
-            hb_tag_to_string(*self, buf.as_mut_ptr() as *mut c_char);
-        }
-        match str::from_utf8(&buf) {
-            Ok(s) => write!(fmt, "hb_tag_t({})", s),
-            Err(_) => write!(fmt, "hb_tag_t({:?})", buf),
-        }
+impl From<u32> for hb_tag_t {
+    fn from(tag: u32) -> hb_tag_t {
+        hb_tag_t(tag)
     }
 }
-*/
+
+impl From<hb_tag_t> for u32 {
+    fn from(tag: hb_tag_t) -> u32 {
+        tag.0
+    }
+}
+
 extern "C" {
     pub fn hb_tag_from_string(str: *const c_char, len: c_int) -> hb_tag_t;
-    pub fn hb_tag_to_string(tag: hb_tag_t, buf: *mut c_char) -> c_void;
+    pub fn hb_tag_to_string(tag: hb_tag_t, buf: *mut c_char);
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -84,12 +89,20 @@ extern "C" {
 #[repr(C)]
 pub struct hb_language_impl_t(cffi::Private);
 
-#[derive(Clone, Copy)]
+/// Languages are interned by HarfBuzz, so two languages are equal exactly
+/// when they point to the same string.
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 #[repr(transparent)]
 pub struct hb_language_t(*const hb_language_impl_t);
 
-/*impl fmt::Debug for hb_language_t {
+/// Unset [`hb_language_t`].
+pub const HB_LANGUAGE_INVALID: hb_language_t = hb_language_t(ptr::null());
+
+impl fmt::Debug for hb_language_t {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        if self.0.is_null() {
+            return fmt.write_str("HB_LANGUAGE_INVALID");
+        }
         let s = unsafe {
             CStr::from_ptr(hb_language_to_string(*self))
         };
@@ -98,7 +111,7 @@ pub struct hb_language_t(*const hb_language_impl_t);
             Err(_) => fmt.write_str("<invalid utf-8 sequence in language tag>"),
         }
     }
-}*/
+}
 
 extern "C" {
     pub fn hb_language_from_string(str: *const c_char, len: c_int) -> hb_language_t;
@@ -106,35 +119,10 @@ extern "C" {
     pub fn hb_language_get_default() -> hb_language_t;
 }
 
-#[derive(Clone, Copy/*, Debug*/)]
-#[repr(C)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
+#[repr(transparent)]
 pub struct hb_script_t(hb_tag_t);
 
-/*impl hb_script_t {
-    #[inline]
-    pub fn from_str(str: &str) -> hb_script_t {
-        let bytes = str.as_bytes();
-        unsafe {
-            hb_script_from_string(bytes.as_ptr() as *const c_char, bytes.len() as c_int)
-        }
-    }
-
-    #[inline]
-    pub fn from_iso15924_tag(tag: hb_tag_t) -> hb_script_t {
-        unsafe { hb_script_from_iso15924_tag(tag) }
-    }
-
-    #[inline]
-    pub fn to_iso15924_tag(self) -> hb_tag_t {
-        unsafe { hb_script_to_iso15924_tag(self) }
-    }
-
-    #[inline]
-    pub fn get_horizontal_direction(self) -> hb_direction_t {
-        unsafe { hb_script_get_horizontal_direction(self) }
-    }
-}*/
-
 extern "C" {
     pub fn hb_script_from_iso15924_tag(tag: hb_tag_t) -> hb_script_t;
     pub fn hb_script_from_string(str: *const c_char, len: c_int) -> hb_script_t;
@@ -142,6 +130,8 @@ extern "C" {
     pub fn hb_script_get_horizontal_direction(script: hb_script_t) -> hb_direction_t;
 }
 
+/// Unset [`hb_script_t`].
+pub const SCRIPT_INVALID: hb_script_t = hb_script_t(hb_tag_t(0));
 pub const SCRIPT_COMMON: hb_script_t = hb_script_t(hb_tag_t::new(*b"Zyyy"));
 pub const SCRIPT_INHERITED: hb_script_t = hb_script_t(hb_tag_t::new(*b"Zinh"));
 pub const SCRIPT_UNKNOWN: hb_script_t = hb_script_t(hb_tag_t::new(*b"Zzzz"));
@@ -292,35 +282,40 @@ pub const SCRIPT_MEDEFAIDRIN: hb_script_t = hb_script_t(hb_tag_t::new(*b"Medf"))
 pub const SCRIPT_OLD_SOGDIAN: hb_script_t = hb_script_t(hb_tag_t::new(*b"Sogo"));
 pub const SCRIPT_SOGDIAN: hb_script_t = hb_script_t(hb_tag_t::new(*b"Sogd"));
 
-pub type hb_user_data_key_t = c_void;
+/// Key for attaching user data to HarfBuzz objects. Only its address
+/// matters, its contents are never used.
+#[repr(C)]
+pub struct hb_user_data_key_t {
+    unused: c_char,
+}
 
-pub type hb_destroy_func_t = extern "C" fn(data: *mut c_void) -> c_void;
+pub type hb_destroy_func_t = Option<unsafe extern "C" fn(data: *mut c_void)>;
 
-#[derive(Clone, Copy)]
+/// Special setting for [`hb_feature_t::start`] to apply the feature from
+/// the start of the buffer.
+pub const HB_FEATURE_GLOBAL_START: c_uint = 0;
+/// Special setting for [`hb_feature_t::end`] to apply the feature to up to
+/// the end of the buffer.
+pub const HB_FEATURE_GLOBAL_END: c_uint = c_uint::MAX;
+
+/// The structure that holds information about requested feature application.
+/// The feature will be applied with the given value to all glyphs which are
+/// in clusters between `start` (inclusive) and `end` (exclusive).
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 #[repr(C)]
 pub struct hb_feature_t {
+    /// The tag of the feature.
     pub tag: hb_tag_t,
+    /// The value of the feature. 0 disables the feature, non-zero (usually 1)
+    /// enables the feature. For features implemented as lookup type 3 (like
+    /// `salt`) the value is a one based index into the alternates.
     pub value: u32,
+    /// The cluster to start applying this feature setting (inclusive).
     pub start: c_uint,
+    /// The cluster to end applying this feature setting (exclusive).
     pub end: c_uint,
 }
 
-/*impl hb_feature_t {
-    #[inline]
-    pub fn from_str(str: &str) -> Option<hb_feature_t> {
-        let bytes = str.as_bytes();
-        let mut feature = unsafe { mem::uninitialized() };
-        let r = unsafe {
-            hb_feature_from_string(
-                bytes.as_ptr() as *const c_char,
-                bytes.len() as c_int,
-                &mut feature,
-            )
-        };
-        if r != 0 { Some(feature) } else { None }
-    }
-}*/
-
 extern "C" {
     pub fn hb_feature_from_string(
         str: *const c_char,
@@ -331,29 +326,13 @@ extern "C" {
     pub fn hb_feature_to_string(feature: *mut hb_feature_t, buf: *mut c_char, size: c_uint);
 }
 
-#[derive(Clone, Copy/*, Debug*/)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 #[repr(C)]
 pub struct hb_variation_t {
     pub tag: hb_tag_t,
     pub value: c_float,
 }
 
-/*impl hb_variation_t {
-    #[inline]
-    pub fn from_str(str: &str) -> Option<hb_variation_t> {
-        let bytes = str.as_bytes();
-        let mut variation = unsafe { mem::uninitialized() };
-        let r = unsafe {
-            hb_variation_from_string(
-                bytes.as_ptr() as *const c_char,
-                bytes.len() as c_int,
-                &mut variation,
-            )
-        };
-        if r != 0 { Some(variation) } else { None }
-    }
-}*/
-
 extern "C" {
     pub fn hb_variation_from_string(
         str: *const c_char,
@@ -17,7 +17,7 @@ pub type hb_reference_table_func_t = extern "C" fn(
     face: *mut hb_face_t,
     tag: hb_tag_t,
     user_data: *mut c_void,
-);
+) -> *mut hb_blob_t;
 
 extern "C" {
     /// Get number of faces on the blob.
@@ -87,5 +87,5 @@ extern "C" {
         face: *mut hb_face_t,
         tag: hb_tag_t,
         blob: *mut hb_blob_t,
-    );
+    ) -> hb_bool_t;
 }
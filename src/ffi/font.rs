@@ -97,7 +97,7 @@ pub type hb_font_get_glyph_advances_func_t = extern "C" fn(
     font: *mut hb_font_t,
     font_data: *mut c_void,
     count: c_uint,
-    first_glyph: *mut hb_codepoint_t,
+    first_glyph: *const hb_codepoint_t,
     glyph_stride: c_uint,
     first_advance: *mut hb_position_t,
     advance_stride: c_uint,
@@ -297,18 +297,18 @@ extern "C" {
     pub fn hb_font_get_glyph_h_advances(
         funcs: *mut hb_font_t,
         count: c_uint,
-        first_glyph: *mut hb_codepoint_t,
+        first_glyph: *const hb_codepoint_t,
         glyph_stride: c_uint,
-        first_advance: *mut hb_codepoint_t,
+        first_advance: *mut hb_position_t,
         advance_stride: c_uint
     );
 
     pub fn hb_font_get_glyph_v_advances(
         funcs: *mut hb_font_t,
         count: c_uint,
-        first_glyph: *mut hb_codepoint_t,
+        first_glyph: *const hb_codepoint_t,
         glyph_stride: c_uint,
-        first_advance: *mut hb_codepoint_t,
+        first_advance: *mut hb_position_t,
         advance_stride: c_uint
     );
 
@@ -364,7 +364,7 @@ extern "C" {
     pub fn hb_font_get_extents_for_direction(
         font: *mut hb_font_t,
         direction: hb_direction_t,
-        extents: *mut hb_glyph_extents_t,
+        extents: *mut hb_font_extents_t,
     );
 
     pub fn hb_font_get_glyph_advance_for_direction(
@@ -379,7 +379,7 @@ extern "C" {
         font: *mut hb_font_t,
         direction: hb_direction_t,
         count: c_uint,
-        first_glyph: *mut hb_codepoint_t,
+        first_glyph: *const hb_codepoint_t,
         glyph_stride: c_uint,
         first_advance: *mut hb_position_t,
         advance_stride: c_uint,
@@ -437,7 +437,7 @@ extern "C" {
         s: *const c_char,
         len: c_int,
         glyph: *mut hb_codepoint_t,
-    );
+    ) -> hb_bool_t;
 
     pub fn hb_font_create(face: *mut hb_face_t) -> *mut hb_font_t;
 
@@ -1,5 +1,7 @@
 //! Bindings to `hb-set.h`.
 
+#![allow(non_camel_case_types)]
+
 use std::os::raw::*;
 
 use crate::ffi::common::*;
@@ -7,7 +9,7 @@ use crate::ffi::common::*;
 #[repr(C)]
 pub struct hb_set_t(cffi::Private);
 
-pub const HB_SET_VALUE_INVALID: hb_codepoint_t = hb_codepoint_t::max_value();
+pub const HB_SET_VALUE_INVALID: hb_codepoint_t = hb_codepoint_t::MAX;
 
 extern "C" {
     pub fn hb_set_create() -> *mut hb_set_t;
@@ -27,8 +29,8 @@ extern "C" {
     ) -> *mut c_void;
     pub fn hb_set_allocation_successful(set: *const hb_set_t) -> hb_bool_t;
     pub fn hb_set_clear(set: *mut hb_set_t);
-    pub fn hb_set_is_empty(set: *const hb_set_t);
-    pub fn hb_set_has(set: *const hb_set_t, codepoint: hb_codepoint_t);
+    pub fn hb_set_is_empty(set: *const hb_set_t) -> hb_bool_t;
+    pub fn hb_set_has(set: *const hb_set_t, codepoint: hb_codepoint_t) -> hb_bool_t;
     pub fn hb_set_add(set: *mut hb_set_t, codepoint: hb_codepoint_t);
     pub fn hb_set_add_range(
         set: *mut hb_set_t,
@@ -41,8 +43,8 @@ extern "C" {
         first: hb_codepoint_t,
         last: hb_codepoint_t,
     );
-    pub fn hb_set_is_equal(set: *const hb_set_t, other: *const hb_set_t);
-    pub fn hb_set_is_subset(set: *const hb_set_t, other: *const hb_set_t);
+    pub fn hb_set_is_equal(set: *const hb_set_t, other: *const hb_set_t) -> hb_bool_t;
+    pub fn hb_set_is_subset(set: *const hb_set_t, other: *const hb_set_t) -> hb_bool_t;
     pub fn hb_set_set(set: *mut hb_set_t, other: *const hb_set_t);
     pub fn hb_set_union(set: *mut hb_set_t, other: *const hb_set_t);
     pub fn hb_set_intersect(set: *mut hb_set_t, other: *const hb_set_t);
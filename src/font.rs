@@ -1,5 +1,7 @@
 use std::{
+    marker::PhantomData,
     mem,
+    ops::{Deref, DerefMut},
     os::raw,
     slice,
 };
@@ -7,10 +9,137 @@ use cffi::Ptr;
 
 use crate::*;
 
-pub struct Font(Ptr<ffi::hb_font_t>);
-impl_ptr!(Font, ffi::hb_font_t);
+/// A font, that is a [`Face`] at a particular size and with particular
+/// settings.
+///
+/// A font can't outlive data borrowed by its face.
+pub struct Font<'a>(Ptr<ffi::hb_font_t>, PhantomData<&'a [u8]>);
 
-pub type FontFuncs = Ptr<ffi::hb_font_funcs_t>;
+impl<'a> Deref for Font<'a> {
+    type Target = ffi::hb_font_t;
+
+    fn deref(&self) -> &ffi::hb_font_t {
+        unsafe { &*Ptr::as_ptr(&self.0) }
+    }
+}
+
+impl<'a> DerefMut for Font<'a> {
+    fn deref_mut(&mut self) -> &mut ffi::hb_font_t {
+        unsafe { &mut *Ptr::as_raw(&mut self.0) }
+    }
+}
+
+/// An immutable font, created with [`Font::freeze()`].
+///
+/// Once made immutable HarfBuzz objects are safe to use from many threads
+/// at once. Unlike [`Font`] this type only gives shared access to the
+/// underlying [`hb_font_t`]. This means that all glyph and metrics queries,
+/// as well as shaping, are available, but methods which change a font, such as
+/// [`set_scale()`], [`set_ppem()`], [`set_ptem()`], [`set_variations()`],
+/// [`set_var_coords_normalized()`], [`set_parent()`] and [`set_face()`], are
+/// not.
+///
+/// Cloning a frozen font creates a new reference to the same underlying
+/// object.
+///
+/// Fonts backed by FreeType can only be frozen with
+/// [`Font::freeze_unchecked()`].
+#[derive(Clone)]
+#[repr(transparent)]
+pub struct FrozenFont<'a>(Font<'a>);
+
+unsafe impl<'a> Send for FrozenFont<'a> {}
+unsafe impl<'a> Sync for FrozenFont<'a> {}
+
+impl<'a> Deref for FrozenFont<'a> {
+    type Target = ffi::hb_font_t;
+
+    fn deref(&self) -> &ffi::hb_font_t {
+        &self.0
+    }
+}
+
+impl<'a> FrozenFont<'a> {
+    /// See [`Font::parent()`].
+    pub fn parent(&self) -> FrozenFont<'a> {
+        // The parent was frozen together with this font.
+        FrozenFont(self.0.parent())
+    }
+
+    /// See [`Font::face()`].
+    pub fn face(&self) -> FrozenFace<'a> {
+        // The face was frozen together with this font, and if it uses
+        // FreeType so did this font.
+        unsafe { self.0.face().freeze_unchecked() }
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_font_t {
+        self.0.as_ptr()
+    }
+}
+
+/// Cloning a font creates a new reference to the same underlying object.
+///
+/// Since the font may then be changed through either handle, accessors which
+/// return its face, parent or variation coordinates return new references or
+/// copies instead of borrows.
+impl<'a> Clone for Font<'a> {
+    fn clone(&self) -> Font<'a> {
+        unsafe {
+            let ptr = ffi::hb_font_reference(self.as_ptr() as *mut _);
+            Font(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+}
+
+#[repr(transparent)]
+pub struct FontFuncs(Ptr<ffi::hb_font_funcs_t>);
+impl_ptr!(FontFuncs, ffi::hb_font_funcs_t);
+
+/// Cloning font functions creates a new reference to the same underlying
+/// object.
+impl Clone for FontFuncs {
+    fn clone(&self) -> FontFuncs {
+        unsafe {
+            let ptr = ffi::hb_font_funcs_reference(self.as_ptr() as *mut _);
+            FontFuncs(Ptr::from_raw(ptr))
+        }
+    }
+}
+
+impl Default for FontFuncs {
+    fn default() -> FontFuncs {
+        FontFuncs::new()
+    }
+}
+
+impl FontFuncs {
+    pub fn new() -> FontFuncs {
+        unsafe {
+            let ptr = ffi::hb_font_funcs_create();
+            FontFuncs(Ptr::from_raw(ptr))
+        }
+    }
+
+    pub fn empty() -> FontFuncs {
+        unsafe {
+            let ptr = ffi::hb_font_funcs_get_empty();
+            FontFuncs(Ptr::from_raw(ptr))
+        }
+    }
+
+    pub fn into_raw(self) -> *mut ffi::hb_font_funcs_t {
+        Ptr::into_raw(self.0)
+    }
+
+    pub fn as_raw(&mut self) -> *mut ffi::hb_font_funcs_t {
+        Ptr::as_raw(&mut self.0)
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_font_funcs_t {
+        Ptr::as_ptr(&self.0)
+    }
+}
 
 impl cffi::Alloc for ffi::hb_font_t {
     fn free(this: *mut Self) {
@@ -24,14 +153,103 @@ impl cffi::Alloc for ffi::hb_font_funcs_t {
     }
 }
 
-impl Font {
-    pub fn create(face: Face) -> Font {
+impl<'a> Font<'a> {
+    pub fn create(mut face: Face<'a>) -> Font<'a> {
         unsafe {
-            let ptr = ffi::hb_font_create(face.into_raw());
-            Font(Ptr::from_raw(ptr))
+            // hb_font_create takes its own reference to the face.
+            let ptr = ffi::hb_font_create(face.as_raw());
+            Font(Ptr::from_raw(ptr), PhantomData)
         }
     }
 
+    /// Create a new font for a shared immutable face.
+    pub fn from_frozen_face(face: &FrozenFace<'a>) -> Font<'a> {
+        unsafe {
+            // hb_font_create takes its own reference to the face.
+            let ptr = ffi::hb_font_create(face.as_ptr() as *mut _);
+            Font(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+
+    pub fn create_sub_font(&mut self) -> Font<'a> {
+        unsafe {
+            let ptr = ffi::hb_font_create_sub_font(self.as_raw());
+            Font(Ptr::from_raw(ptr), PhantomData)
+        }
+    }
+
+    /// Sets parent font of `font`.
+    pub fn set_parent(&mut self, mut parent: Font<'a>) {
+        unsafe { ffi::hb_font_set_parent(self.as_raw(), parent.as_raw()) }
+    }
+
+    /// Sets font-face of `font`.
+    pub fn set_face(&mut self, mut face: Face<'a>) {
+        unsafe { ffi::hb_font_set_face(self.as_raw(), face.as_raw()) }
+    }
+
+    /// Get a new reference to the parent of this font.
+    ///
+    /// A new reference is returned rather than a borrow, since the parent
+    /// can be replaced, and freed, through another handle to this font.
+    pub fn parent(&self) -> Font<'a> {
+        unsafe {
+            let ptr = ffi::hb_font_get_parent(self.as_ptr() as *mut _);
+            Font(Ptr::from_raw(ffi::hb_font_reference(ptr)), PhantomData)
+        }
+    }
+
+    /// Get a new reference to the face of this font.
+    ///
+    /// A new reference is returned rather than a borrow, since the face
+    /// can be replaced, and freed, through another handle to this font.
+    pub fn face(&self) -> Face<'a> {
+        unsafe {
+            let ptr = ffi::hb_font_get_face(self.as_ptr() as *mut _);
+            Face::from_raw(ffi::hb_face_reference(ptr))
+        }
+    }
+
+    /// Make this font, its parents and its face immutable, allowing it to be
+    /// shared between threads.
+    ///
+    /// See [`FrozenFont`].
+    ///
+    /// Panics if this font, one of its parents, or their faces use FreeType,
+    /// as FreeType faces can't be used from many threads at once. Use
+    /// [`freeze_unchecked()`] to freeze such fonts anyway.
+    pub fn freeze(self) -> FrozenFont<'a> {
+        if self.uses_freetype() {
+            panic!("Cannot freeze hb_font_t backed by FreeType");
+        }
+        unsafe { self.freeze_unchecked() }
+    }
+
+    /// Make this font, its parents and its face immutable without checking
+    /// whether they are backed by FreeType.
+    ///
+    /// ## Safety
+    ///
+    /// If this font, one of its parents, or their faces use FreeType, the
+    /// FreeType faces must not be accessed from more than one thread at
+    /// a time, neither directly nor through this font.
+    pub unsafe fn freeze_unchecked(mut self) -> FrozenFont<'a> {
+        ffi::hb_font_make_immutable(self.as_raw());
+        // HarfBuzz freezes parents but not faces, but we need them to be
+        // immutable as well, since they could still be changed through
+        // another handle.
+        let empty = ffi::hb_font_get_empty();
+        let mut font = self.as_raw();
+        while !font.is_null() && font != empty {
+            let face = ffi::hb_font_get_face(font);
+            if !face.is_null() {
+                ffi::hb_face_make_immutable(face);
+            }
+            font = ffi::hb_font_get_parent(font);
+        }
+        FrozenFont(self)
+    }
+
     pub fn into_raw(self) -> *mut ffi::hb_font_t {
         Ptr::into_raw(self.0)
     }
@@ -56,109 +274,118 @@ impl Font {
 // - hb_font_set_funcs
 // - hb_font_set_funcs_data
 impl ffi::hb_font_t {
-    pub fn h_extents(&mut self) -> Option<FontExtents> {
+    /// Pointer to this font for passing to HarfBuzz's query functions.
+    ///
+    /// XXX: HarfBuzz declares them as taking a mutable font, but they don't
+    /// modify it in any way visible to us, and they are safe to call
+    /// concurrently on an immutable font.
+    fn query_ptr(&self) -> *mut ffi::hb_font_t {
+        self as *const ffi::hb_font_t as *mut ffi::hb_font_t
+    }
+
+    pub fn h_extents(&self) -> Option<FontExtents> {
         let mut extents = Default::default();
         let r = unsafe {
-            ffi::hb_font_get_h_extents(self, &mut extents)
+            ffi::hb_font_get_h_extents(self.query_ptr(), &mut extents)
         };
         if r != 0 { Some(extents) } else { None }
     }
 
-    pub fn v_extents(&mut self) -> Option<FontExtents> {
+    pub fn v_extents(&self) -> Option<FontExtents> {
         let mut extents = Default::default();
         let r = unsafe {
-            ffi::hb_font_get_v_extents(self, &mut extents)
+            ffi::hb_font_get_v_extents(self.query_ptr(), &mut extents)
         };
         if r != 0 { Some(extents) } else { None }
     }
 
     pub fn glyph(
-        &mut self,
+        &self,
         unicode: Codepoint,
         variation_selector: Codepoint,
     ) -> Option<Codepoint> {
         let mut cp = 0;
         let r = unsafe {
-            ffi::hb_font_get_glyph(self, unicode, variation_selector, &mut cp)
+            ffi::hb_font_get_glyph(self.query_ptr(), unicode, variation_selector, &mut cp)
         };
         if r != 0 { Some(cp) } else { None }
     }
 
     pub fn nominal_glyph(
-        &mut self,
+        &self,
         unicode: Codepoint,
     ) -> Option<Codepoint> {
         let mut cp = 0;
         let r = unsafe {
-            ffi::hb_font_get_nominal_glyph(self, unicode, &mut cp)
+            ffi::hb_font_get_nominal_glyph(self.query_ptr(), unicode, &mut cp)
         };
         if r != 0 { Some(cp) } else { None }
     }
 
     pub fn variation_glyph(
-        &mut self,
+        &self,
         unicode: Codepoint,
         variation_selector: Codepoint,
     ) -> Option<Codepoint> {
         let mut cp = 0;
         let r = unsafe {
-            ffi::hb_font_get_variation_glyph(self, unicode, variation_selector, &mut cp)
+            ffi::hb_font_get_variation_glyph(self.query_ptr(), unicode, variation_selector, &mut cp)
         };
         if r != 0 { Some(cp) } else { None }
     }
 
-    pub fn glyph_h_advance(&mut self, cp: Codepoint) -> Position {
-        unsafe { ffi::hb_font_get_glyph_h_advance(self, cp) }
+    pub fn glyph_h_advance(&self, cp: Codepoint) -> Position {
+        unsafe { ffi::hb_font_get_glyph_h_advance(self.query_ptr(), cp) }
     }
 
-    pub fn glyph_v_advance(&mut self, top: Codepoint) -> Position {
-        unsafe { ffi::hb_font_get_glyph_v_advance(self, top) }
+    pub fn glyph_v_advance(&self, top: Codepoint) -> Position {
+        unsafe { ffi::hb_font_get_glyph_v_advance(self.query_ptr(), top) }
     }
 
-    pub fn glyph_h_origin(&mut self, glyph: Codepoint)
+    pub fn glyph_h_origin(&self, glyph: Codepoint)
     -> Option<(Position, Position)> {
         let mut x = 0;
         let mut y = 0;
         let r = unsafe {
-            ffi::hb_font_get_glyph_h_origin(self, glyph, &mut x, &mut y)
+            ffi::hb_font_get_glyph_h_origin(self.query_ptr(), glyph, &mut x, &mut y)
         };
         if r != 0 { Some((x, y)) } else { None }
     }
 
-    pub fn glyph_v_origin(&mut self, glyph: Codepoint)
+    pub fn glyph_v_origin(&self, glyph: Codepoint)
     -> Option<(Position, Position)> {
         let mut x = 0;
         let mut y = 0;
         let r = unsafe {
-            ffi::hb_font_get_glyph_v_origin(self, glyph, &mut x, &mut y)
+            ffi::hb_font_get_glyph_v_origin(self.query_ptr(), glyph, &mut x, &mut y)
         };
         if r != 0 { Some((x, y)) } else { None }
     }
 
-    pub fn glyph_extents(&mut self, glyph: Codepoint) -> Option<GlyphExtents> {
+    pub fn glyph_extents(&self, glyph: Codepoint) -> Option<GlyphExtents> {
         let mut extents = Default::default();
         let r = unsafe {
-            ffi::hb_font_get_glyph_extents(self, glyph, &mut extents)
+            ffi::hb_font_get_glyph_extents(self.query_ptr(), glyph, &mut extents)
         };
         if r != 0 { Some(extents) } else { None }
     }
 
-    pub fn glyph_contour_point(&mut self, glyph: Codepoint, index: u32)
+    pub fn glyph_contour_point(&self, glyph: Codepoint, index: u32)
     -> Option<(Position, Position)> {
         let mut x = 0;
         let mut y = 0;
         let r = unsafe {
-            ffi::hb_font_get_glyph_contour_point(self, glyph, index, &mut x, &mut y)
+            ffi::hb_font_get_glyph_contour_point(self.query_ptr(), glyph, index, &mut x, &mut y)
         };
         if r != 0 { Some((x, y)) } else { None }
     }
 
-    pub fn glyph_from_name(&mut self, name: &str) -> Option<Codepoint> {
+    pub fn glyph_from_name(&self, name: &str) -> Option<Codepoint> {
         let bytes = name.as_bytes();
         let mut cp = 0;
         let r = unsafe {
             ffi::hb_font_get_glyph_from_name(
-                self,
+                self.query_ptr(),
                 bytes.as_ptr() as *const raw::c_char,
                 bytes.len() as raw::c_int,
                 &mut cp,
@@ -167,16 +394,16 @@ impl ffi::hb_font_t {
         if r != 0 { Some(cp) } else { None }
     }
 
-    pub fn extents_for_direction(&mut self, direction: Direction) -> GlyphExtents {
+    pub fn extents_for_direction(&self, direction: Direction) -> FontExtents {
         let mut extents = Default::default();
         unsafe {
-            ffi::hb_font_get_extents_for_direction(self, direction, &mut extents);
+            ffi::hb_font_get_extents_for_direction(self.query_ptr(), direction, &mut extents);
         }
         extents
     }
 
     pub fn glyph_advance_for_direction(
-        &mut self,
+        &self,
         glyph: Codepoint,
         direction: Direction,
     ) -> (Position, Position) {
@@ -184,13 +411,13 @@ impl ffi::hb_font_t {
         let mut y = 0;
         unsafe {
             ffi::hb_font_get_glyph_advance_for_direction(
-                self, glyph, direction, &mut x, &mut y);
+                self.query_ptr(), glyph, direction, &mut x, &mut y);
         }
         (x, y)
     }
 
     pub fn get_glyph_origin_for_direction(
-        &mut self,
+        &self,
         glyph: Codepoint,
         direction: Direction,
     ) -> (Position, Position) {
@@ -198,13 +425,13 @@ impl ffi::hb_font_t {
         let mut y = 0;
         unsafe {
             ffi::hb_font_get_glyph_origin_for_direction(
-                self, glyph, direction, &mut x, &mut y);
+                self.query_ptr(), glyph, direction, &mut x, &mut y);
         }
         (x, y)
     }
 
     pub fn add_glyph_origin_for_direction(
-        &mut self,
+        &self,
         glyph: Codepoint,
         direction: Direction,
     ) -> (Position, Position) {
@@ -212,13 +439,13 @@ impl ffi::hb_font_t {
         let mut y = 0;
         unsafe {
             ffi::hb_font_add_glyph_origin_for_direction(
-                self, glyph, direction, &mut x, &mut y);
+                self.query_ptr(), glyph, direction, &mut x, &mut y);
         }
         (x, y)
     }
 
     pub fn subtract_glyph_origin_for_direction(
-        &mut self,
+        &self,
         glyph: Codepoint,
         direction: Direction,
     ) -> (Position, Position) {
@@ -226,26 +453,26 @@ impl ffi::hb_font_t {
         let mut y = 0;
         unsafe {
             ffi::hb_font_subtract_glyph_origin_for_direction(
-                self, glyph, direction, &mut x, &mut y);
+                self.query_ptr(), glyph, direction, &mut x, &mut y);
         }
         (x, y)
     }
 
     pub fn glyph_extents_for_origin(
-        &mut self,
+        &self,
         glyph: Codepoint,
         direction: Direction,
     ) -> Option<GlyphExtents> {
         let mut extents = Default::default();
         let r = unsafe {
             ffi::hb_font_get_glyph_extents_for_origin(
-                self, glyph, direction, &mut extents)
+                self.query_ptr(), glyph, direction, &mut extents)
         };
         if r != 0 { Some(extents) } else { None }
     }
 
     pub fn glyph_contour_point_for_origin(
-        &mut self,
+        &self,
         glyph: Codepoint,
         index: u32,
         direction: Direction,
@@ -254,31 +481,23 @@ impl ffi::hb_font_t {
         let mut y = 0;
         let r = unsafe {
             ffi::hb_font_get_glyph_contour_point_for_origin(
-                self, glyph, index, direction, &mut x, &mut y)
+                self.query_ptr(), glyph, index, direction, &mut x, &mut y)
         };
         if r != 0 { Some((x, y)) } else { None }
     }
 
-    pub fn glyph_from_string(&mut self, s: &str) -> Codepoint {
+    pub fn glyph_from_string(&self, s: &str) -> Option<Codepoint> {
         let bytes = s.as_bytes();
         let mut cp = 0;
-        unsafe {
+        let r = unsafe {
             ffi::hb_font_glyph_from_string(
-                self,
+                self.query_ptr(),
                 bytes.as_ptr() as *const raw::c_char,
                 bytes.len() as raw::c_int,
                 &mut cp,
-            );
-        }
-        cp
-    }
-
-    // TODO: does this need to be mutable?
-    pub fn create_sub_font(&mut self) -> Font {
-        unsafe {
-            let ptr = ffi::hb_font_create_sub_font(self);
-            Font(Ptr::from_raw(ptr))
-        }
+            )
+        };
+        if r != 0 { Some(cp) } else { None }
     }
 
     pub fn is_immutable(&self) -> bool {
@@ -290,28 +509,6 @@ impl ffi::hb_font_t {
         unsafe { ffi::hb_font_make_immutable(self) }
     }
 
-    pub fn parent(&self) -> &ffi::hb_font_t {
-        // XXX: since we're returning a non-mutable reference there should
-        // be no problems with aliasing.
-        unsafe { mem::transmute(ffi::hb_font_get_parent(mem::transmute(self))) }
-    }
-
-    /// Sets parent font of `font`.
-    pub fn set_parent(&mut self, parent: Font) {
-        unsafe { ffi::hb_font_set_parent(self, parent.into_raw()) }
-    }
-
-    pub fn face(&self) -> &ffi::hb_face_t {
-        // XXX: since we're returning a non-mutable reference there should
-        // be no problems with aliasing.
-        unsafe { mem::transmute(ffi::hb_font_get_face(mem::transmute(self))) }
-    }
-
-    /// Sets font-face of `font`.
-    pub fn set_face(&mut self, face: Face) {
-        unsafe { ffi::hb_font_set_face(self, face.into_raw()) }
-    }
-
     pub fn scale(&self) -> (i32, i32) {
         let mut x = 0;
         let mut y = 0;
@@ -361,6 +558,15 @@ impl ffi::hb_font_t {
         }
     }
 
+    /// Parse `variations` with [`parse_variations()`] and apply them to this
+    /// font. The font is left unchanged if parsing fails.
+    pub fn set_variations_str(&mut self, variations: &str)
+    -> Result<(), VariationParseError> {
+        let variations = parse_variations(variations)?;
+        self.set_variations(&variations);
+        Ok(())
+    }
+
     pub fn set_var_coords_normalized(&mut self, coords: &[i32]) {
         unsafe {
             ffi::hb_font_set_var_coords_normalized(
@@ -371,17 +577,23 @@ impl ffi::hb_font_t {
         }
     }
 
-    pub fn var_coords_normalized(&self) -> &[i32] {
+    /// Return a copy of this font's normalized variation coordinates.
+    ///
+    /// The coordinates are copied since they would be freed if they were
+    /// changed through another handle to this font.
+    pub fn var_coords_normalized(&self) -> Vec<i32> {
         let mut len = 0;
         unsafe {
             let buf = ffi::hb_font_get_var_coords_normalized(
-                // XXX: Since as of 1.8.8 hb_font_get_var_coords_normalized
-                // is constant, and we're returning an immutable reference,
-                // this should be safe.
+                // XXX: As of 1.8.8 hb_font_get_var_coords_normalized
+                // is constant, so this is safe.
                 mem::transmute(self),
                 &mut len,
             );
-            slice::from_raw_parts(buf, len as usize)
+            if len == 0 {
+                return Vec::new();
+            }
+            slice::from_raw_parts(buf, len as usize).to_vec()
         }
     }
 }
@@ -390,16 +602,44 @@ impl ffi::hb_font_t {
 use freetype;
 
 #[cfg(feature = "freetype")]
-impl Font {
-    pub fn from_ft_face(mut face: freetype::Face) -> Font {
+impl Font<'static> {
+    pub fn from_ft_face(mut face: freetype::Face) -> Font<'static> {
         face.reference();
         unsafe {
             let ptr = ffi::hb_ft_font_create_referenced(face.raw_mut());
-            Font(Ptr::from_raw(ptr))
+            Font(Ptr::from_raw(ptr), PhantomData)
         }
     }
 }
 
+impl ffi::hb_font_t {
+    /// Check whether this font, one of its parents, or their faces use
+    /// FreeType.
+    #[cfg(feature = "freetype")]
+    pub(crate) fn uses_freetype(&self) -> bool {
+        let empty = unsafe { ffi::hb_font_get_empty() };
+        let mut font = self.query_ptr();
+        while !font.is_null() && font != empty {
+            unsafe {
+                if !ffi::hb_ft_font_get_face(font).is_null() {
+                    return true;
+                }
+                let face = ffi::hb_font_get_face(font);
+                if !face.is_null() && (*face).uses_freetype() {
+                    return true;
+                }
+                font = ffi::hb_font_get_parent(font);
+            }
+        }
+        false
+    }
+
+    #[cfg(not(feature = "freetype"))]
+    pub(crate) fn uses_freetype(&self) -> bool {
+        false
+    }
+}
+
 #[cfg(feature = "freetype")]
 impl ffi::hb_font_t {
     /// Call when size or variations settings on underlying [`FT_Face`] change.
@@ -420,20 +660,6 @@ impl ffi::hb_font_t {
 // - hb_font_funcs_set_user_data
 // - hb_font_funcs_get_user_data
 impl ffi::hb_font_funcs_t {
-    pub fn new() -> FontFuncs {
-        unsafe {
-            let ptr = ffi::hb_font_funcs_create();
-            Ptr::from_raw(ptr)
-        }
-    }
-
-    pub fn empty() -> FontFuncs {
-        unsafe {
-            let ptr = ffi::hb_font_funcs_get_empty();
-            Ptr::from_raw(ptr)
-        }
-    }
-
     pub fn is_immutable(&self) -> bool {
         // XXX: As of 1.8.8 hb_font_funcs_is_immutable is constant, so this
         // is safe.
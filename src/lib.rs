@@ -5,14 +5,20 @@ pub mod ffi;
 
 mod blob;
 mod buffer;
+mod common;
 mod face;
+mod fallback;
 mod font;
+mod set;
 mod shape;
 
 pub use self::blob::*;
 pub use self::buffer::*;
+pub use self::common::*;
 pub use self::face::*;
+pub use self::fallback::*;
 pub use self::font::*;
+pub use self::set::*;
 pub use self::shape::*;
 
 pub type Codepoint = ffi::hb_codepoint_t;
@@ -23,3 +29,5 @@ pub type GlyphExtents = ffi::hb_glyph_extents_t;
 pub type FontExtents = ffi::hb_font_extents_t;
 pub type Tag = ffi::hb_tag_t;
 pub type Feature = ffi::hb_feature_t;
+pub type Language = ffi::hb_language_t;
+pub type Script = ffi::hb_script_t;
@@ -0,0 +1,296 @@
+use cffi::Ptr;
+use std::{
+    fmt,
+    iter::FromIterator,
+    ops::RangeInclusive,
+};
+
+use crate::*;
+
+/// A set of integers, usually Unicode code points or glyph indices.
+#[repr(transparent)]
+pub struct Set(Ptr<ffi::hb_set_t>);
+impl_ptr!(Set, ffi::hb_set_t);
+
+impl cffi::Alloc for ffi::hb_set_t {
+    fn free(this: *mut Self) {
+        unsafe { ffi::hb_set_destroy(this) }
+    }
+}
+
+impl Set {
+    /// Create a new empty set.
+    pub fn new() -> Set {
+        unsafe {
+            let raw = ffi::hb_set_create();
+            if 0 == ffi::hb_set_allocation_successful(raw) {
+                panic!("Cannot allocate hb_set_t: out of memory");
+            }
+            Set(Ptr::from_raw(raw))
+        }
+    }
+
+    pub fn into_raw(self) -> *mut ffi::hb_set_t {
+        Ptr::into_raw(self.0)
+    }
+
+    pub fn as_ptr(&self) -> *const ffi::hb_set_t {
+        Ptr::as_ptr(&self.0)
+    }
+
+    pub fn as_raw(&mut self) -> *mut ffi::hb_set_t {
+        Ptr::as_raw(&mut self.0)
+    }
+}
+
+// unimplemented:
+// - hb_set_set_user_data
+// - hb_set_get_user_data
+impl ffi::hb_set_t {
+    /// HarfBuzz doesn't report allocation failures when modifying a set,
+    /// instead it marks the set as failed and ignores further changes.
+    fn check_allocation(&self) {
+        if 0 == unsafe { ffi::hb_set_allocation_successful(self) } {
+            panic!("Cannot allocate space for hb_set_t: out of memory");
+        }
+    }
+
+    /// Return the number of elements in this set.
+    pub fn len(&self) -> usize {
+        unsafe { ffi::hb_set_get_population(self) as usize }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        unsafe { ffi::hb_set_is_empty(self) != 0 }
+    }
+
+    pub fn contains(&self, value: Codepoint) -> bool {
+        unsafe { ffi::hb_set_has(self, value) != 0 }
+    }
+
+    /// Return the smallest element of this set, or `None` if it is empty.
+    pub fn min(&self) -> Option<Codepoint> {
+        let value = unsafe { ffi::hb_set_get_min(self) };
+        if value != ffi::HB_SET_VALUE_INVALID { Some(value) } else { None }
+    }
+
+    /// Return the largest element of this set, or `None` if it is empty.
+    pub fn max(&self) -> Option<Codepoint> {
+        let value = unsafe { ffi::hb_set_get_max(self) };
+        if value != ffi::HB_SET_VALUE_INVALID { Some(value) } else { None }
+    }
+
+    /// Check whether all elements of this set are also in `other`.
+    pub fn is_subset(&self, other: &ffi::hb_set_t) -> bool {
+        unsafe { ffi::hb_set_is_subset(self, other) != 0 }
+    }
+
+    /// Remove all elements from this set.
+    pub fn clear(&mut self) {
+        unsafe { ffi::hb_set_clear(self) }
+    }
+
+    /// Add `value` to this set.
+    ///
+    /// Note that [`HB_SET_VALUE_INVALID`] can't be stored in a set.
+    pub fn insert(&mut self, value: Codepoint) {
+        unsafe { ffi::hb_set_add(self, value) }
+        self.check_allocation();
+    }
+
+    /// Remove `value` from this set.
+    pub fn remove(&mut self, value: Codepoint) {
+        unsafe { ffi::hb_set_del(self, value) }
+    }
+
+    /// Add all values in `range` to this set.
+    pub fn insert_range(&mut self, range: RangeInclusive<Codepoint>) {
+        unsafe { ffi::hb_set_add_range(self, *range.start(), *range.end()) }
+        self.check_allocation();
+    }
+
+    /// Remove all values in `range` from this set.
+    pub fn remove_range(&mut self, range: RangeInclusive<Codepoint>) {
+        unsafe { ffi::hb_set_del_range(self, *range.start(), *range.end()) }
+    }
+
+    /// Add all elements of `other` to this set.
+    pub fn union(&mut self, other: &ffi::hb_set_t) {
+        unsafe { ffi::hb_set_union(self, other) }
+        self.check_allocation();
+    }
+
+    /// Remove all elements not in `other` from this set.
+    pub fn intersect(&mut self, other: &ffi::hb_set_t) {
+        unsafe { ffi::hb_set_intersect(self, other) }
+    }
+
+    /// Remove all elements of `other` from this set.
+    pub fn subtract(&mut self, other: &ffi::hb_set_t) {
+        unsafe { ffi::hb_set_subtract(self, other) }
+    }
+
+    /// Leave in this set only elements which are either in this set or in
+    /// `other`, but not in both.
+    pub fn symmetric_difference(&mut self, other: &ffi::hb_set_t) {
+        unsafe { ffi::hb_set_symmetric_difference(self, other) }
+        self.check_allocation();
+    }
+
+    /// Iterate over elements of this set in ascending order.
+    pub fn iter(&self) -> SetIter<'_> {
+        SetIter {
+            set: self,
+            last: ffi::HB_SET_VALUE_INVALID,
+            done: false,
+        }
+    }
+
+    /// Iterate over ranges of consecutive elements of this set in ascending
+    /// order.
+    pub fn ranges(&self) -> SetRanges<'_> {
+        SetRanges {
+            set: self,
+            last: ffi::HB_SET_VALUE_INVALID,
+            done: false,
+        }
+    }
+}
+
+/// Cloning a set creates a new set with the same elements.
+impl Clone for Set {
+    fn clone(&self) -> Set {
+        let mut set = Set::new();
+        unsafe { ffi::hb_set_set(set.as_raw(), self.as_ptr()) }
+        set.check_allocation();
+        set
+    }
+}
+
+impl Default for Set {
+    fn default() -> Set {
+        Set::new()
+    }
+}
+
+impl PartialEq for Set {
+    fn eq(&self, other: &Set) -> bool {
+        unsafe { ffi::hb_set_is_equal(self.as_ptr(), other.as_ptr()) != 0 }
+    }
+}
+
+impl Eq for Set {}
+
+impl fmt::Debug for Set {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<Codepoint> for Set {
+    fn from_iter<I: IntoIterator<Item = Codepoint>>(iter: I) -> Set {
+        let mut set = Set::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<Codepoint> for Set {
+    fn extend<I: IntoIterator<Item = Codepoint>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<'s> IntoIterator for &'s Set {
+    type Item = Codepoint;
+    type IntoIter = SetIter<'s>;
+
+    fn into_iter(self) -> SetIter<'s> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Set {
+    type Item = Codepoint;
+    type IntoIter = SetIntoIter;
+
+    fn into_iter(self) -> SetIntoIter {
+        SetIntoIter {
+            set: self,
+            last: ffi::HB_SET_VALUE_INVALID,
+            done: false,
+        }
+    }
+}
+
+/// Iterator over elements of a set, returned by [`hb_set_t::iter()`].
+pub struct SetIter<'s> {
+    set: &'s ffi::hb_set_t,
+    last: Codepoint,
+    done: bool,
+}
+
+impl<'s> Iterator for SetIter<'s> {
+    type Item = Codepoint;
+
+    fn next(&mut self) -> Option<Codepoint> {
+        next_value(self.set, &mut self.last, &mut self.done)
+    }
+}
+
+/// Owning iterator over elements of a set.
+pub struct SetIntoIter {
+    set: Set,
+    last: Codepoint,
+    done: bool,
+}
+
+impl Iterator for SetIntoIter {
+    type Item = Codepoint;
+
+    fn next(&mut self) -> Option<Codepoint> {
+        next_value(&self.set, &mut self.last, &mut self.done)
+    }
+}
+
+/// Once there are no more elements `hb_set_next` starts over from the first
+/// one, so we have to remember when we're done.
+fn next_value(set: &ffi::hb_set_t, last: &mut Codepoint, done: &mut bool)
+-> Option<Codepoint> {
+    if *done {
+        return None;
+    }
+    if unsafe { ffi::hb_set_next(set, last) } != 0 {
+        Some(*last)
+    } else {
+        *done = true;
+        None
+    }
+}
+
+/// Iterator over ranges of consecutive elements in a set, returned by
+/// [`hb_set_t::ranges()`].
+pub struct SetRanges<'s> {
+    set: &'s ffi::hb_set_t,
+    last: Codepoint,
+    done: bool,
+}
+
+impl<'s> Iterator for SetRanges<'s> {
+    type Item = RangeInclusive<Codepoint>;
+
+    fn next(&mut self) -> Option<RangeInclusive<Codepoint>> {
+        if self.done {
+            return None;
+        }
+        let mut first = 0;
+        if unsafe { ffi::hb_set_next_range(self.set, &mut first, &mut self.last) } != 0 {
+            Some(first..=self.last)
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
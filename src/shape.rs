@@ -7,15 +7,17 @@ use crate::*;
 /// the features applied during shaping.
 pub fn shape(
     font: &ffi::hb_font_t,
-    buffer: &mut ffi::hb_buffer_t,
+    buffer: UnicodeBuffer,
     features: &[Feature],
-) {
+) -> GlyphBuffer {
     unsafe {
+        let buffer = buffer.into_raw();
         ffi::hb_shape(
             mem::transmute(font),
             buffer,
             features.as_ptr(),
             features.len() as raw::c_uint,
-        )
+        );
+        GlyphBuffer::from_shaped(buffer)
     }
 }
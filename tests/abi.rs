@@ -0,0 +1,725 @@
+//! Checks declarations in `harfbuzz::ffi` against the installed HarfBuzz
+//! headers.
+//!
+//! The checks are emitted as a C file full of `_Static_assert`s which is then
+//! compiled (but not linked) with the system C compiler, `$CC` if set.
+//!
+//! Functions are checked by comparing their type with the function pointer
+//! type Rust declares for them. Constness of data pointed to by parameters
+//! is not checked, as it changed between HarfBuzz versions without affecting
+//! ABI, and neither is signedness of `char` pointed to by parameters.
+
+use harfbuzz::ffi;
+use std::{
+    any::TypeId,
+    env,
+    fs,
+    mem,
+    os::raw::{c_char, c_void},
+    process::Command,
+};
+use bitflags::Flags;
+
+/// A Rust type which has a C equivalent.
+trait CType {
+    /// Name of the C type, in a form usable in `sizeof` and casts.
+    fn name() -> String;
+
+    /// Names of C types which may be declared instead of this type when it's
+    /// pointed to by a function parameter.
+    fn pointee_names() -> Vec<String> {
+        vec![Self::name()]
+    }
+
+    /// Names of C types which may be declared instead of this type for
+    /// a function parameter.
+    fn param_names() -> Vec<String> {
+        vec![Self::name()]
+    }
+}
+
+macro_rules! c_types {
+    ($($ty:ty => $name:expr,)*) => {
+        $(impl CType for $ty {
+            fn name() -> String {
+                $name.into()
+            }
+        })*
+    };
+}
+
+c_types! {
+    () => "void",
+    c_void => "void",
+    i32 => "int",
+    u32 => "unsigned int",
+    u16 => "uint16_t",
+    f32 => "float",
+    ffi::hb_blob_t => "hb_blob_t",
+    ffi::hb_buffer_t => "hb_buffer_t",
+    ffi::hb_face_t => "hb_face_t",
+    ffi::hb_font_t => "hb_font_t",
+    ffi::hb_font_funcs_t => "hb_font_funcs_t",
+    ffi::hb_set_t => "hb_set_t",
+    ffi::hb_unicode_funcs_t => "hb_unicode_funcs_t",
+    ffi::hb_tag_t => "hb_tag_t",
+    ffi::hb_script_t => "hb_script_t",
+    ffi::hb_language_t => "hb_language_t",
+    ffi::hb_direction_t => "hb_direction_t",
+    ffi::hb_memory_mode_t => "hb_memory_mode_t",
+    ffi::hb_var_int_t => "hb_var_int_t",
+    ffi::hb_feature_t => "hb_feature_t",
+    ffi::hb_variation_t => "hb_variation_t",
+    ffi::hb_user_data_key_t => "hb_user_data_key_t",
+    ffi::hb_font_extents_t => "hb_font_extents_t",
+    ffi::hb_glyph_extents_t => "hb_glyph_extents_t",
+    ffi::hb_glyph_info_t => "hb_glyph_info_t",
+    ffi::hb_glyph_flags_t => "hb_glyph_flags_t",
+    ffi::hb_glyph_position_t => "hb_glyph_position_t",
+    ffi::hb_segment_properties_t => "hb_segment_properties_t",
+    ffi::hb_buffer_content_type_t => "hb_buffer_content_type_t",
+    ffi::hb_buffer_flags_t => "hb_buffer_flags_t",
+    ffi::hb_buffer_cluster_level_t => "hb_buffer_cluster_level_t",
+    ffi::hb_buffer_serialize_flags_t => "hb_buffer_serialize_flags_t",
+    ffi::hb_buffer_serialize_format_t => "hb_buffer_serialize_format_t",
+    ffi::hb_buffer_diff_flags_t => "hb_buffer_diff_flags_t",
+    ffi::hb_unicode_general_category_t => "hb_unicode_general_category_t",
+    ffi::hb_unicode_combining_class_t => "hb_unicode_combining_class_t",
+}
+
+#[cfg(feature = "freetype")]
+c_types! {
+    freetype::ffi::FT_FaceRec => "FT_FaceRec",
+}
+
+/// `c_char` is either `i8` or `u8` depending on the platform.
+fn byte_name<T: 'static>(signed: bool) -> String {
+    if TypeId::of::<T>() == TypeId::of::<c_char>() {
+        "char".into()
+    } else if signed {
+        "int8_t".into()
+    } else {
+        "uint8_t".into()
+    }
+}
+
+impl CType for i8 {
+    fn name() -> String {
+        byte_name::<i8>(true)
+    }
+
+    fn pointee_names() -> Vec<String> {
+        vec!["char".into(), "int8_t".into()]
+    }
+}
+
+impl CType for u8 {
+    fn name() -> String {
+        byte_name::<u8>(false)
+    }
+
+    fn pointee_names() -> Vec<String> {
+        vec!["char".into(), "uint8_t".into()]
+    }
+}
+
+/// Wrap `name` so that it can be followed by a declarator.
+fn wrap(name: String) -> String {
+    if name.contains(['*', '(']) {
+        format!("__typeof__({})", name)
+    } else {
+        name
+    }
+}
+
+/// Names of both constant and mutable pointers to `T`.
+fn pointer_param_names<T: CType>() -> Vec<String> {
+    T::pointee_names()
+        .into_iter()
+        .flat_map(|name| {
+            let name = wrap(name);
+            vec![format!("{} const *", name), format!("{} *", name)]
+        })
+        .collect()
+}
+
+impl<T: CType> CType for *const T {
+    fn name() -> String {
+        format!("{} const *", wrap(T::name()))
+    }
+
+    fn param_names() -> Vec<String> {
+        pointer_param_names::<T>()
+    }
+}
+
+impl<T: CType> CType for *mut T {
+    fn name() -> String {
+        format!("{} *", wrap(T::name()))
+    }
+
+    fn param_names() -> Vec<String> {
+        pointer_param_names::<T>()
+    }
+}
+
+/// Nullable function pointers.
+impl<T: CType> CType for Option<T> {
+    fn name() -> String {
+        T::name()
+    }
+}
+
+fn function_pointer(ret: String, args: &[String]) -> String {
+    let args = if args.is_empty() { "void".into() } else { args.join(", ") };
+    format!("{} (*)({})", wrap(ret), args)
+}
+
+/// All combinations of one item from each of `lists`.
+fn combinations(lists: &[Vec<String>]) -> Vec<Vec<String>> {
+    lists.iter().fold(vec![vec![]], |combinations, list| {
+        combinations.iter()
+            .flat_map(|combination| list.iter().map(move |item| {
+                let mut combination = combination.clone();
+                combination.push(item.clone());
+                combination
+            }))
+            .collect()
+    })
+}
+
+/// Generate a check that the type of function `name` is one of function
+/// pointer types returning `ret` and taking one of `params` for each
+/// parameter.
+fn check_function(name: &str, ret: String, params: &[Vec<String>]) -> String {
+    let alternatives = combinations(params)
+        .into_iter()
+        .map(|params| format!(
+            "__builtin_types_compatible_p(__typeof__(&{}), {})",
+            name,
+            function_pointer(ret.clone(), &params),
+        ))
+        .collect::<Vec<_>>();
+    format!("_Static_assert({}, \"{}\");\n", alternatives.join(" || "), name)
+}
+
+/// A Rust function pointer type.
+trait Signature {
+    /// Generate a check that function `name` has this signature.
+    fn check(name: &str) -> String;
+}
+
+macro_rules! signatures {
+    ($($arg:ident)*) => {
+        impl<R: CType, $($arg: CType),*> Signature for unsafe extern "C" fn($($arg),*) -> R {
+            fn check(name: &str) -> String {
+                check_function(name, R::name(), &[$($arg::param_names()),*])
+            }
+        }
+
+        impl<R: CType, $($arg: CType),*> CType for unsafe extern "C" fn($($arg),*) -> R {
+            fn name() -> String {
+                function_pointer(R::name(), &[$($arg::name()),*])
+            }
+        }
+
+        impl<R: CType, $($arg: CType),*> CType for extern "C" fn($($arg),*) -> R {
+            fn name() -> String {
+                function_pointer(R::name(), &[$($arg::name()),*])
+            }
+        }
+    };
+}
+
+signatures!();
+signatures!(A);
+signatures!(A B);
+signatures!(A B C);
+signatures!(A B C D);
+signatures!(A B C D E);
+signatures!(A B C D E F);
+signatures!(A B C D E F G);
+signatures!(A B C D E F G H);
+signatures!(A B C D E F G H I);
+
+fn check_signature<F: Signature>(_: F, name: &str) -> String {
+    F::check(name)
+}
+
+macro_rules! functions {
+    ($($name:ident($($arg:tt),*);)*) => {{
+        let mut out = String::new();
+        $(out += &check_signature(
+            ffi::$name as unsafe extern "C" fn($($arg),*) -> _,
+            stringify!($name),
+        );)*
+        out
+    }};
+}
+
+fn check_layout<T: CType>() -> String {
+    format!(
+        "_Static_assert(sizeof({0}) == {1} && _Alignof({0}) == {2}, \"layout of {0}\");\n",
+        T::name(),
+        mem::size_of::<T>(),
+        mem::align_of::<T>(),
+    )
+}
+
+fn check_value(name: &str, value: i64) -> String {
+    format!("_Static_assert((long long) {0} == {1}LL, \"{0}\");\n", name, value)
+}
+
+macro_rules! values {
+    ($prefix:expr, $ty:ident: $($variant:ident),* $(,)?) => {{
+        let mut out = String::new();
+        $(out += &check_value(
+            &format!("{}{}", $prefix, stringify!($variant)),
+            ffi::$ty::$variant as i64,
+        );)*
+        out
+    }};
+}
+
+fn flags<T: Flags<Bits = i32>>(prefix: &str) -> String {
+    T::FLAGS.iter()
+        .map(|flag| check_value(
+            &format!("{}{}", prefix, flag.name()),
+            flag.value().bits() as i64,
+        ))
+        .collect()
+}
+
+macro_rules! scripts {
+    ($($script:ident),* $(,)?) => {{
+        let mut out = String::new();
+        $(out += &check_value(
+            concat!("HB_", stringify!($script)),
+            unsafe { mem::transmute::<ffi::hb_script_t, u32>(ffi::$script) } as i64,
+        );)*
+        out
+    }};
+}
+
+fn checks() -> String {
+    let mut out = String::from("#include <hb.h>\n");
+    if cfg!(feature = "freetype") {
+        out += "#include <hb-ft.h>\n";
+    }
+
+    out += &check_layout::<ffi::hb_tag_t>();
+    out += &check_layout::<ffi::hb_script_t>();
+    out += &check_layout::<ffi::hb_language_t>();
+    out += &check_layout::<ffi::hb_direction_t>();
+    out += &check_layout::<ffi::hb_memory_mode_t>();
+    out += &check_layout::<ffi::hb_var_int_t>();
+    out += &check_layout::<ffi::hb_feature_t>();
+    out += &check_layout::<ffi::hb_variation_t>();
+    out += &check_layout::<ffi::hb_user_data_key_t>();
+    out += &check_layout::<ffi::hb_font_extents_t>();
+    out += &check_layout::<ffi::hb_glyph_extents_t>();
+    out += &check_layout::<ffi::hb_glyph_info_t>();
+    out += &check_layout::<ffi::hb_glyph_flags_t>();
+    out += &check_layout::<ffi::hb_glyph_position_t>();
+    out += &check_layout::<ffi::hb_segment_properties_t>();
+    out += &check_layout::<ffi::hb_buffer_content_type_t>();
+    out += &check_layout::<ffi::hb_buffer_flags_t>();
+    out += &check_layout::<ffi::hb_buffer_cluster_level_t>();
+    out += &check_layout::<ffi::hb_buffer_serialize_flags_t>();
+    out += &check_layout::<ffi::hb_buffer_serialize_format_t>();
+    out += &check_layout::<ffi::hb_buffer_diff_flags_t>();
+    out += &check_layout::<ffi::hb_unicode_general_category_t>();
+    out += &check_layout::<ffi::hb_unicode_combining_class_t>();
+
+    out += &values!("HB_MEMORY_MODE_", hb_memory_mode_t:
+        DUPLICATE, READONLY, WRITABLE, READONLY_MAY_MAKE_WRITABLE,
+    );
+    out += &values!("", hb_direction_t:
+        HB_DIRECTION_INVALID, HB_DIRECTION_LTR, HB_DIRECTION_RTL,
+        HB_DIRECTION_TTB, HB_DIRECTION_BTT,
+    );
+    out += &values!("HB_BUFFER_CONTENT_TYPE_", hb_buffer_content_type_t:
+        INVALID, UNICODE, GLYPHS,
+    );
+    out += &values!("HB_BUFFER_CLUSTER_LEVEL_", hb_buffer_cluster_level_t:
+        MONOTONE_GRAPHEMES, MONOTONE_CHARACTERS, CHARACTERS,
+    );
+    out += &values!("HB_BUFFER_SERIALIZE_FORMAT_", hb_buffer_serialize_format_t:
+        TEXT, JSON, INVALID,
+    );
+    out += &values!("HB_UNICODE_GENERAL_CATEGORY_", hb_unicode_general_category_t:
+        CONTROL, FORMAT, UNASSIGNED, PRIVATE_USE, SURROGATE, LOWERCASE_LETTER,
+        MODIFIER_LETTER, OTHER_LETTER, TITLECASE_LETTER, UPPERCASE_LETTER,
+        SPACING_MARK, ENCLOSING_MARK, NON_SPACING_MARK, DECIMAL_NUMBER, LETTER_NUMBER,
+        OTHER_NUMBER, CONNECT_PUNCTUATION, DASH_PUNCTUATION, CLOSE_PUNCTUATION,
+        FINAL_PUNCTUATION, INITIAL_PUNCTUATION, OTHER_PUNCTUATION, OPEN_PUNCTUATION,
+        CURRENCY_SYMBOL, MODIFIER_SYMBOL, MATH_SYMBOL, OTHER_SYMBOL, LINE_SEPARATOR,
+        PARAGRAPH_SEPARATOR, SPACE_SEPARATOR,
+    );
+    out += &values!("HB_UNICODE_COMBINING_CLASS_", hb_unicode_combining_class_t:
+        NOT_REORDERED, OVERLAY, NUKTA, KANA_VOICING, VIRAMA, CCC10, CCC11, CCC12,
+        CCC13, CCC14, CCC15, CCC16, CCC17, CCC18, CCC19, CCC20, CCC21, CCC22, CCC23,
+        CCC24, CCC25, CCC26, CCC27, CCC28, CCC29, CCC30, CCC31, CCC32, CCC33, CCC34,
+        CCC35, CCC36, CCC84, CCC91, CCC103, CCC107, CCC118, CCC122, CCC129, CCC130,
+        CCC133, ATTACHED_BELOW_LEFT, ATTACHED_BELOW, ATTACHED_ABOVE,
+        ATTACHED_ABOVE_RIGHT, BELOW_LEFT, BELOW, BELOW_RIGHT, LEFT, RIGHT, ABOVE_LEFT,
+        ABOVE, ABOVE_RIGHT, DOUBLE_BELOW, DOUBLE_ABOVE, IOTA_SUBSCRIPT, INVALID,
+    );
+    out += &flags::<ffi::hb_glyph_flags_t>("HB_GLYPH_FLAG_");
+    out += &flags::<ffi::hb_buffer_flags_t>("HB_");
+    out += &flags::<ffi::hb_buffer_serialize_flags_t>("HB_BUFFER_SERIALIZE_FLAG_");
+    out += &flags::<ffi::hb_buffer_diff_flags_t>("HB_BUFFER_DIFF_FLAG_");
+    out += &check_value("HB_FEATURE_GLOBAL_START", ffi::HB_FEATURE_GLOBAL_START as i64);
+    out += &check_value("HB_FEATURE_GLOBAL_END", ffi::HB_FEATURE_GLOBAL_END as i64);
+    out += &check_value(
+        "HB_BUFFER_REPLACEMENT_CODEPOINT_DEFAULT",
+        ffi::HB_BUFFER_REPLACEMENT_CODEPOINT_DEFAULT as i64,
+    );
+    out += &check_value("HB_SET_VALUE_INVALID", ffi::HB_SET_VALUE_INVALID as i64);
+    out += &scripts!(
+        SCRIPT_INVALID, SCRIPT_COMMON, SCRIPT_INHERITED, SCRIPT_UNKNOWN, SCRIPT_ARABIC,
+        SCRIPT_ARMENIAN, SCRIPT_BENGALI, SCRIPT_CYRILLIC, SCRIPT_DEVANAGARI,
+        SCRIPT_GEORGIAN, SCRIPT_GREEK, SCRIPT_GUJARATI, SCRIPT_GURMUKHI, SCRIPT_HANGUL,
+        SCRIPT_HAN, SCRIPT_HEBREW, SCRIPT_HIRAGANA, SCRIPT_KANNADA, SCRIPT_KATAKANA,
+        SCRIPT_LAO, SCRIPT_LATIN, SCRIPT_MALAYALAM, SCRIPT_ORIYA, SCRIPT_TAMIL,
+        SCRIPT_TELUGU, SCRIPT_THAI, SCRIPT_TIBETAN, SCRIPT_BOPOMOFO, SCRIPT_BRAILLE,
+        SCRIPT_CANADIAN_SYLLABICS, SCRIPT_CHEROKEE, SCRIPT_ETHIOPIC, SCRIPT_KHMER,
+        SCRIPT_MONGOLIAN, SCRIPT_MYANMAR, SCRIPT_OGHAM, SCRIPT_RUNIC, SCRIPT_SINHALA,
+        SCRIPT_SYRIAC, SCRIPT_THAANA, SCRIPT_YI, SCRIPT_DESERET, SCRIPT_GOTHIC,
+        SCRIPT_OLD_ITALIC, SCRIPT_BUHID, SCRIPT_HANUNOO, SCRIPT_TAGALOG,
+        SCRIPT_TAGBANWA, SCRIPT_CYPRIOT, SCRIPT_LIMBU, SCRIPT_LINEAR_B, SCRIPT_OSMANYA,
+        SCRIPT_SHAVIAN, SCRIPT_TAI_LE, SCRIPT_UGARITIC, SCRIPT_BUGINESE, SCRIPT_COPTIC,
+        SCRIPT_GLAGOLITIC, SCRIPT_KHAROSHTHI, SCRIPT_NEW_TAI_LUE, SCRIPT_OLD_PERSIAN,
+        SCRIPT_SYLOTI_NAGRI, SCRIPT_TIFINAGH, SCRIPT_BALINESE, SCRIPT_CUNEIFORM,
+        SCRIPT_NKO, SCRIPT_PHAGS_PA, SCRIPT_PHOENICIAN, SCRIPT_CARIAN, SCRIPT_CHAM,
+        SCRIPT_KAYAH_LI, SCRIPT_LEPCHA, SCRIPT_LYCIAN, SCRIPT_LYDIAN, SCRIPT_OL_CHIKI,
+        SCRIPT_REJANG, SCRIPT_SAURASHTRA, SCRIPT_SUNDANESE, SCRIPT_VAI, SCRIPT_AVESTAN,
+        SCRIPT_BAMUM, SCRIPT_EGYPTIAN_HIEROGLYPHS, SCRIPT_IMPERIAL_ARAMAIC,
+        SCRIPT_INSCRIPTIONAL_PAHLAVI, SCRIPT_INSCRIPTIONAL_PARTHIAN, SCRIPT_JAVANESE,
+        SCRIPT_KAITHI, SCRIPT_LISU, SCRIPT_MEETEI_MAYEK, SCRIPT_OLD_SOUTH_ARABIAN,
+        SCRIPT_OLD_TURKIC, SCRIPT_SAMARITAN, SCRIPT_TAI_THAM, SCRIPT_TAI_VIET,
+        SCRIPT_BATAK, SCRIPT_BRAHMI, SCRIPT_MANDAIC, SCRIPT_CHAKMA,
+        SCRIPT_MEROITIC_CURSIVE, SCRIPT_MEROITIC_HIEROGLYPHS, SCRIPT_MIAO,
+        SCRIPT_SHARADA, SCRIPT_SORA_SOMPENG, SCRIPT_TAKRI, SCRIPT_BASSA_VAH,
+        SCRIPT_CAUCASIAN_ALBANIAN, SCRIPT_DUPLOYAN, SCRIPT_ELBASAN, SCRIPT_GRANTHA,
+        SCRIPT_KHOJKI, SCRIPT_KHUDAWADI, SCRIPT_LINEAR_A, SCRIPT_MAHAJANI,
+        SCRIPT_MANICHAEAN, SCRIPT_MENDE_KIKAKUI, SCRIPT_MODI, SCRIPT_MRO,
+        SCRIPT_NABATAEAN, SCRIPT_OLD_NORTH_ARABIAN, SCRIPT_OLD_PERMIC,
+        SCRIPT_PAHAWH_HMONG, SCRIPT_PALMYRENE, SCRIPT_PAU_CIN_HAU,
+        SCRIPT_PSALTER_PAHLAVI, SCRIPT_SIDDHAM, SCRIPT_TIRHUTA, SCRIPT_WARANG_CITI,
+        SCRIPT_AHOM, SCRIPT_ANATOLIAN_HIEROGLYPHS, SCRIPT_HATRAN, SCRIPT_MULTANI,
+        SCRIPT_OLD_HUNGARIAN, SCRIPT_SIGNWRITING, SCRIPT_ADLAM, SCRIPT_BHAIKSUKI,
+        SCRIPT_MARCHEN, SCRIPT_OSAGE, SCRIPT_TANGUT, SCRIPT_NEWA, SCRIPT_MASARAM_GONDI,
+        SCRIPT_NUSHU, SCRIPT_SOYOMBO, SCRIPT_ZANABAZAR_SQUARE, SCRIPT_DOGRA,
+        SCRIPT_GUNJALA_GONDI, SCRIPT_HANIFI_ROHINGYA, SCRIPT_MAKASAR,
+        SCRIPT_MEDEFAIDRIN, SCRIPT_OLD_SOGDIAN, SCRIPT_SOGDIAN,
+    );
+
+    out += &functions! {
+        // hb-common.h
+        hb_tag_from_string(_, _);
+        hb_tag_to_string(_, _);
+        hb_direction_from_string(_, _);
+        hb_direction_to_string(_);
+        hb_language_from_string(_, _);
+        hb_language_to_string(_);
+        hb_language_get_default();
+        hb_script_from_iso15924_tag(_);
+        hb_script_from_string(_, _);
+        hb_script_to_iso15924_tag(_);
+        hb_script_get_horizontal_direction(_);
+        hb_feature_from_string(_, _, _);
+        hb_feature_to_string(_, _, _);
+        hb_variation_from_string(_, _, _);
+        hb_variation_to_string(_, _, _);
+
+        // hb-blob.h
+        hb_blob_create(_, _, _, _, _);
+        hb_blob_create_sub_blob(_, _, _);
+        hb_blob_copy_writable_or_fail(_);
+        hb_blob_get_empty();
+        hb_blob_reference(_);
+        hb_blob_destroy(_);
+        hb_blob_set_user_data(_, _, _, _, _);
+        hb_blob_get_user_data(_, _);
+        hb_blob_make_immutable(_);
+        hb_blob_is_immutable(_);
+        hb_blob_get_length(_);
+        hb_blob_get_data(_, _);
+        hb_blob_get_data_writable(_, _);
+        hb_blob_create_from_file(_);
+
+        // hb-face.h
+        hb_face_count(_);
+        hb_face_create(_, _);
+        hb_face_create_for_tables(_, _, _);
+        hb_face_get_empty();
+        hb_face_reference(_);
+        hb_face_destroy(_);
+        hb_face_set_user_data(_, _, _, _, _);
+        hb_face_get_user_data(_, _);
+        hb_face_make_immutable(_);
+        hb_face_is_immutable(_);
+        hb_face_reference_table(_, _);
+        hb_face_reference_blob(_);
+        hb_face_set_index(_, _);
+        hb_face_get_index(_);
+        hb_face_set_upem(_, _);
+        hb_face_get_upem(_);
+        hb_face_set_glyph_count(_, _);
+        hb_face_get_glyph_count(_);
+        hb_face_get_table_tags(_, _, _, _);
+        hb_face_collect_unicodes(_, _);
+        hb_face_collect_variation_selectors(_, _);
+        hb_face_collect_variation_unicodes(_, _, _);
+        hb_face_builder_create();
+        hb_face_builder_add_table(_, _, _);
+
+        // hb-font.h
+        hb_font_funcs_create();
+        hb_font_funcs_get_empty();
+        hb_font_funcs_reference(_);
+        hb_font_funcs_destroy(_);
+        hb_font_funcs_set_user_data(_, _, _, _, _);
+        hb_font_funcs_get_user_data(_, _);
+        hb_font_funcs_make_immutable(_);
+        hb_font_funcs_is_immutable(_);
+        hb_font_funcs_set_font_h_extents_func(_, _, _, _);
+        hb_font_funcs_set_font_v_extents_func(_, _, _, _);
+        hb_font_funcs_set_nominal_glyph_func(_, _, _, _);
+        hb_font_funcs_set_variation_glyph_func(_, _, _, _);
+        hb_font_funcs_set_glyph_h_advance_func(_, _, _, _);
+        hb_font_funcs_set_glyph_v_advance_func(_, _, _, _);
+        hb_font_funcs_set_glyph_h_advances_func(_, _, _, _);
+        hb_font_funcs_set_glyph_v_advances_func(_, _, _, _);
+        hb_font_funcs_set_glyph_h_origin_func(_, _, _, _);
+        hb_font_funcs_set_glyph_v_origin_func(_, _, _, _);
+        hb_font_funcs_set_glyph_extents_func(_, _, _, _);
+        hb_font_funcs_set_glyph_contour_point_func(_, _, _, _);
+        hb_font_funcs_set_glyph_name_func(_, _, _, _);
+        hb_font_funcs_set_glyph_from_name_func(_, _, _, _);
+        hb_font_get_h_extents(_, _);
+        hb_font_get_v_extents(_, _);
+        hb_font_get_nominal_glyph(_, _, _);
+        hb_font_get_variation_glyph(_, _, _, _);
+        hb_font_get_glyph_h_advance(_, _);
+        hb_font_get_glyph_v_advance(_, _);
+        hb_font_get_glyph_h_advances(_, _, _, _, _, _);
+        hb_font_get_glyph_v_advances(_, _, _, _, _, _);
+        hb_font_get_glyph_h_origin(_, _, _, _);
+        hb_font_get_glyph_v_origin(_, _, _, _);
+        hb_font_get_glyph_extents(_, _, _);
+        hb_font_get_glyph_contour_point(_, _, _, _, _);
+        hb_font_get_glyph_name(_, _, _, _);
+        hb_font_get_glyph_from_name(_, _, _, _);
+        hb_font_get_glyph(_, _, _, _);
+        hb_font_get_extents_for_direction(_, _, _);
+        hb_font_get_glyph_advance_for_direction(_, _, _, _, _);
+        hb_font_get_glyph_advances_for_direction(_, _, _, _, _, _, _);
+        hb_font_get_glyph_origin_for_direction(_, _, _, _, _);
+        hb_font_add_glyph_origin_for_direction(_, _, _, _, _);
+        hb_font_subtract_glyph_origin_for_direction(_, _, _, _, _);
+        hb_font_get_glyph_extents_for_origin(_, _, _, _);
+        hb_font_get_glyph_contour_point_for_origin(_, _, _, _, _, _);
+        hb_font_glyph_to_string(_, _, _, _);
+        hb_font_glyph_from_string(_, _, _, _);
+        hb_font_create(_);
+        hb_font_create_sub_font(_);
+        hb_font_get_empty();
+        hb_font_reference(_);
+        hb_font_destroy(_);
+        hb_font_set_user_data(_, _, _, _, _);
+        hb_font_get_user_data(_, _);
+        hb_font_make_immutable(_);
+        hb_font_is_immutable(_);
+        hb_font_set_parent(_, _);
+        hb_font_get_parent(_);
+        hb_font_set_face(_, _);
+        hb_font_get_face(_);
+        hb_font_set_funcs(_, _, _, _);
+        hb_font_set_funcs_data(_, _, _);
+        hb_font_set_scale(_, _, _);
+        hb_font_get_scale(_, _, _);
+        hb_font_set_ppem(_, _, _);
+        hb_font_get_ppem(_, _, _);
+        hb_font_set_ptem(_, _);
+        hb_font_get_ptem(_);
+        hb_font_set_variations(_, _, _);
+        hb_font_set_var_coords_design(_, _, _);
+        hb_font_set_var_coords_normalized(_, _, _);
+        hb_font_get_var_coords_normalized(_, _);
+
+        // hb-set.h
+        hb_set_create();
+        hb_set_get_empty();
+        hb_set_reference(_);
+        hb_set_destroy(_);
+        hb_set_set_user_data(_, _, _, _, _);
+        hb_set_get_user_data(_, _);
+        hb_set_allocation_successful(_);
+        hb_set_clear(_);
+        hb_set_is_empty(_);
+        hb_set_has(_, _);
+        hb_set_add(_, _);
+        hb_set_add_range(_, _, _);
+        hb_set_del(_, _);
+        hb_set_del_range(_, _, _);
+        hb_set_is_equal(_, _);
+        hb_set_is_subset(_, _);
+        hb_set_set(_, _);
+        hb_set_union(_, _);
+        hb_set_intersect(_, _);
+        hb_set_subtract(_, _);
+        hb_set_symmetric_difference(_, _);
+        hb_set_get_population(_);
+        hb_set_get_min(_);
+        hb_set_get_max(_);
+        hb_set_next(_, _);
+        hb_set_previous(_, _);
+        hb_set_next_range(_, _, _);
+        hb_set_previous_range(_, _, _);
+
+        // hb-buffer.h
+        hb_glyph_info_get_glyph_flags(_);
+        hb_segment_properties_equal(_, _);
+        hb_segment_properties_hash(_);
+        hb_buffer_create();
+        hb_buffer_get_empty();
+        hb_buffer_reference(_);
+        hb_buffer_destroy(_);
+        hb_buffer_set_user_data(_, _, _, _, _);
+        hb_buffer_get_user_data(_, _);
+        hb_buffer_set_content_type(_, _);
+        hb_buffer_get_content_type(_);
+        hb_buffer_set_unicode_funcs(_, _);
+        hb_buffer_get_unicode_funcs(_);
+        hb_buffer_set_direction(_, _);
+        hb_buffer_get_direction(_);
+        hb_buffer_set_script(_, _);
+        hb_buffer_get_script(_);
+        hb_buffer_set_language(_, _);
+        hb_buffer_get_language(_);
+        hb_buffer_set_segment_properties(_, _);
+        hb_buffer_get_segment_properties(_, _);
+        hb_buffer_guess_segment_properties(_);
+        hb_buffer_set_flags(_, _);
+        hb_buffer_get_flags(_);
+        hb_buffer_set_cluster_level(_, _);
+        hb_buffer_get_cluster_level(_);
+        hb_buffer_set_replacement_codepoint(_, _);
+        hb_buffer_get_replacement_codepoint(_);
+        hb_buffer_reset(_);
+        hb_buffer_clear_contents(_);
+        hb_buffer_pre_allocate(_, _);
+        hb_buffer_allocation_successful(_);
+        hb_buffer_reverse(_);
+        hb_buffer_reverse_range(_, _, _);
+        hb_buffer_reverse_clusters(_);
+        hb_buffer_add(_, _, _);
+        hb_buffer_add_utf8(_, _, _, _, _);
+        hb_buffer_add_utf16(_, _, _, _, _);
+        hb_buffer_add_utf32(_, _, _, _, _);
+        hb_buffer_add_latin1(_, _, _, _, _);
+        hb_buffer_add_codepoints(_, _, _, _, _);
+        hb_buffer_append(_, _, _, _);
+        hb_buffer_set_length(_, _);
+        hb_buffer_get_length(_);
+        hb_buffer_get_glyph_infos(_, _);
+        hb_buffer_get_glyph_positions(_, _);
+        hb_buffer_normalize_glyphs(_);
+        hb_buffer_serialize_format_from_string(_, _);
+        hb_buffer_serialize_format_to_string(_);
+        hb_buffer_serialize_list_formats();
+        hb_buffer_serialize_glyphs(_, _, _, _, _, _, _, _, _);
+        hb_buffer_deserialize_glyphs(_, _, _, _, _, _);
+        hb_buffer_diff(_, _, _, _);
+        hb_buffer_set_message_func(_, _, _, _);
+
+        // hb-shape.h
+        hb_shape(_, _, _, _);
+        hb_shape_full(_, _, _, _, _);
+        hb_shape_list_shapers();
+
+        // hb-unicode.h
+        hb_unicode_funcs_get_default();
+        hb_unicode_funcs_create(_);
+        hb_unicode_funcs_get_empty();
+        hb_unicode_funcs_reference(_);
+        hb_unicode_funcs_destroy(_);
+        hb_unicode_funcs_set_user_data(_, _, _, _, _);
+        hb_unicode_funcs_get_user_data(_, _);
+        hb_unicode_funcs_make_immutable(_);
+        hb_unicode_funcs_is_immutable(_);
+        hb_unicode_funcs_get_parent(_);
+        hb_unicode_funcs_set_combining_class_func(_, _, _, _);
+        hb_unicode_funcs_set_general_category_func(_, _, _, _);
+        hb_unicode_funcs_set_mirroring_func(_, _, _, _);
+        hb_unicode_funcs_set_script_func(_, _, _, _);
+        hb_unicode_funcs_set_compose_func(_, _, _, _);
+        hb_unicode_funcs_set_decompose_func(_, _, _, _);
+        hb_unicode_combining_class(_, _);
+        hb_unicode_general_category(_, _);
+        hb_unicode_mirroring(_, _);
+        hb_unicode_script(_, _);
+        hb_unicode_compose(_, _, _, _);
+        hb_unicode_decompose(_, _, _, _);
+    };
+
+    #[cfg(feature = "freetype")]
+    {
+        out += &functions! {
+            // hb-ft.h
+            hb_ft_face_create(_, _);
+            hb_ft_face_create_cached(_);
+            hb_ft_face_create_referenced(_);
+            hb_ft_font_create(_, _);
+            hb_ft_font_create_referenced(_);
+            hb_ft_font_get_face(_);
+            hb_ft_font_set_load_flags(_, _);
+            hb_ft_font_get_load_flags(_);
+            hb_ft_font_changed(_);
+            hb_ft_font_set_funcs(_);
+        };
+    }
+
+    out
+}
+
+#[test]
+fn abi() {
+    let mut libraries = vec!["harfbuzz"];
+    if cfg!(feature = "freetype") {
+        libraries.push("freetype2");
+    }
+
+    let mut cc = Command::new(env::var("CC").unwrap_or_else(|_| "cc".into()));
+    for name in libraries {
+        let library = pkg_config::Config::new()
+            .cargo_metadata(false)
+            .probe(name)
+            .unwrap_or_else(|err| panic!("could not locate {}: {}", name, err));
+        for path in library.include_paths {
+            cc.arg("-I").arg(path);
+        }
+    }
+
+    let source = format!("{}/abi.c", env!("CARGO_TARGET_TMPDIR"));
+    fs::write(&source, checks()).expect("could not write test source");
+
+    let output = cc
+        .args(&[
+            "-fsyntax-only",
+            "-std=gnu11",
+            "-Werror",
+            "-Wno-deprecated-declarations",
+        ])
+        .arg(&source)
+        .output()
+        .expect("could not run the C compiler");
+
+    assert!(
+        output.status.success(),
+        "declarations don't match HarfBuzz headers:\n{}",
+        String::from_utf8_lossy(&output.stderr),
+    );
+}
@@ -0,0 +1,35 @@
+use harfbuzz::*;
+
+#[test]
+fn glyph_buffer_from_glyphs() {
+    let buffer = [(3, 0), (4, 1), (5, 1)].iter().copied().collect::<GlyphBuffer>();
+    assert_eq!(buffer.content_type(), ContentType::GLYPHS);
+    let glyphs = buffer.infos().iter()
+        .map(|info| (info.glyph_id(), info.cluster()))
+        .collect::<Vec<_>>();
+    assert_eq!(glyphs, [(3, 0), (4, 1), (5, 1)]);
+    assert!(buffer.positions().iter().all(|position| position.x_advance == 0));
+}
+
+#[test]
+fn truncate_glyph_buffer_to_empty() {
+    let mut buffer = GlyphBuffer::new();
+    buffer.push(3, 0);
+    buffer.push(4, 1);
+    buffer.truncate(0);
+    assert!(buffer.is_empty());
+    assert_eq!(buffer.content_type(), ContentType::GLYPHS);
+
+    buffer.push(5, 0);
+    assert_eq!(buffer.content_type(), ContentType::GLYPHS);
+    assert_eq!(buffer.len(), 1);
+    assert_eq!(buffer.infos()[0].glyph_id(), 5);
+}
+
+#[test]
+fn empty_glyph_buffer() {
+    let mut buffer = GlyphBuffer::new();
+    assert!(buffer.infos().is_empty());
+    assert!(buffer.positions_mut().is_empty());
+    buffer.normalize_glyphs();
+}
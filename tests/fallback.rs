@@ -0,0 +1,98 @@
+use harfbuzz::*;
+
+/// Build a minimal font with `upem` units per em, mapping each character
+/// of `chars` to glyphs starting from 1, and giving all glyphs `advance`.
+fn font(upem: u16, chars: &[char], advance: u16) -> Font<'static> {
+    let glyph_count = chars.len() as u16 + 1;
+
+    let mut head = Vec::new();
+    head.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // version
+    head.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // fontRevision
+    head.extend_from_slice(&0u32.to_be_bytes()); // checkSumAdjustment
+    head.extend_from_slice(&0x5f0f_3cf5u32.to_be_bytes()); // magicNumber
+    head.extend_from_slice(&0u16.to_be_bytes()); // flags
+    head.extend_from_slice(&upem.to_be_bytes()); // unitsPerEm
+    head.extend_from_slice(&[0; 16]); // created, modified
+    head.extend_from_slice(&[0; 8]); // xMin, yMin, xMax, yMax
+    head.extend_from_slice(&[0; 6]); // macStyle, lowestRecPPEM, fontDirectionHint
+    head.extend_from_slice(&[0; 4]); // indexToLocFormat, glyphDataFormat
+
+    let mut maxp = Vec::new();
+    maxp.extend_from_slice(&0x0000_5000u32.to_be_bytes()); // version 0.5
+    maxp.extend_from_slice(&glyph_count.to_be_bytes());
+
+    let mut hhea = Vec::new();
+    hhea.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // version
+    hhea.extend_from_slice(&[0; 28]); // ascender to metricDataFormat
+    hhea.extend_from_slice(&glyph_count.to_be_bytes()); // numberOfHMetrics
+
+    let mut hmtx = Vec::new();
+    for _ in 0..glyph_count {
+        hmtx.extend_from_slice(&advance.to_be_bytes());
+        hmtx.extend_from_slice(&0i16.to_be_bytes());
+    }
+
+    // A single format 12 subtable for the Windows Unicode full repertoire.
+    let mut cmap = Vec::new();
+    cmap.extend_from_slice(&0u16.to_be_bytes()); // version
+    cmap.extend_from_slice(&1u16.to_be_bytes()); // numTables
+    cmap.extend_from_slice(&3u16.to_be_bytes()); // platformID
+    cmap.extend_from_slice(&10u16.to_be_bytes()); // encodingID
+    cmap.extend_from_slice(&12u32.to_be_bytes()); // offset
+    cmap.extend_from_slice(&12u16.to_be_bytes()); // format
+    cmap.extend_from_slice(&0u16.to_be_bytes()); // reserved
+    cmap.extend_from_slice(&(16 + 12 * chars.len() as u32).to_be_bytes()); // length
+    cmap.extend_from_slice(&0u32.to_be_bytes()); // language
+    cmap.extend_from_slice(&(chars.len() as u32).to_be_bytes()); // numGroups
+    let mut sorted = chars.iter().enumerate().collect::<Vec<_>>();
+    sorted.sort_by_key(|&(_, &chr)| chr);
+    for (inx, &chr) in sorted {
+        cmap.extend_from_slice(&(chr as u32).to_be_bytes()); // startCharCode
+        cmap.extend_from_slice(&(chr as u32).to_be_bytes()); // endCharCode
+        cmap.extend_from_slice(&(inx as u32 + 1).to_be_bytes()); // startGlyphID
+    }
+
+    let mut builder = FaceBuilder::new();
+    builder.add_table(tag!("head"), Blob::from_vec(head));
+    builder.add_table(tag!("maxp"), Blob::from_vec(maxp));
+    builder.add_table(tag!("hhea"), Blob::from_vec(hhea));
+    builder.add_table(tag!("hmtx"), Blob::from_vec(hmtx));
+    builder.add_table(tag!("cmap"), Blob::from_vec(cmap));
+    Font::create(builder.build())
+}
+
+#[test]
+fn shape_with_fallback_font() {
+    let primary = font(1000, &['a', 'b', 'c'], 500);
+    let fallback = font(2000, &['x'], 1200);
+    let shaper = FallbackShaper::new(vec![primary, fallback]);
+
+    let glyphs = shaper.shape("abxc", &[])
+        .iter()
+        .map(|glyph| (glyph.font, glyph.glyph, glyph.cluster, glyph.position.x_advance))
+        .collect::<Vec<_>>();
+    assert_eq!(glyphs, [
+        (0, 1, 0, 500),
+        (0, 2, 1, 500),
+        // Advance of 1200 in 2000 units per em, scaled to 1000 units per em.
+        (1, 1, 2, 600),
+        (0, 3, 3, 500),
+    ]);
+}
+
+#[test]
+fn shape_without_any_glyphs() {
+    let primary = font(1000, &['a'], 500);
+    let fallback = font(1000, &['b'], 500);
+    let shaper = FallbackShaper::new(vec![primary, fallback]);
+
+    let glyphs = shaper.shape("z", &[]);
+    assert_eq!(glyphs.len(), 1);
+    assert_eq!((glyphs[0].font, glyphs[0].glyph), (1, 0));
+}
+
+#[test]
+fn shape_empty_text() {
+    let shaper = FallbackShaper::new(vec![font(1000, &['a'], 500)]);
+    assert!(shaper.shape("", &[]).is_empty());
+}